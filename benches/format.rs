@@ -0,0 +1,80 @@
+use criterion::{Criterion, Throughput, criterion_group, criterion_main};
+use gdfmt::format_code;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// Counts heap allocations made by Rust code; the parser's own C allocations
+/// are not included.
+struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+const SIZES: [usize; 3] = [1, 10, 100];
+
+fn get_script(copies: usize) -> String {
+    let sample = std::fs::read_to_string("samples/styleguide.gd").unwrap();
+    let body = sample
+        .lines()
+        .skip_while(|line| !line.starts_with("signal"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut script = String::from("extends Node\n");
+
+    for index in 0..copies {
+        script.push_str(&format!("\n\nclass Part{}:\n", index));
+        for line in body.lines() {
+            if !line.is_empty() {
+                script.push('\t');
+            }
+            script.push_str(line);
+            script.push('\n');
+        }
+    }
+
+    script
+}
+
+fn format_scripts(c: &mut Criterion) {
+    let mut group = c.benchmark_group("format");
+
+    for copies in SIZES {
+        let script = get_script(copies);
+        let name = format!("{}_lines", script.lines().count());
+        let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+        format_code(&script).unwrap();
+        println!(
+            "format/{}: {} allocations",
+            name,
+            ALLOCATIONS.load(Ordering::Relaxed) - allocations
+        );
+        group.throughput(Throughput::Bytes(script.len() as u64));
+        group.bench_function(name, |b| {
+            b.iter(|| format_code(black_box(&script)).unwrap())
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, format_scripts);
+criterion_main!(benches);
@@ -0,0 +1,272 @@
+use crate::config::Config;
+use std::collections::BTreeMap;
+use std::fs::{create_dir_all, read_to_string, rename, write};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const CACHE_DIR_NAME: &str = ".godot";
+pub const CACHE_FILE_NAME: &str = "gdfmt_cache";
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+/// Sources not seen for this long are dropped from the cache.
+const MAX_AGE: u64 = 30 * SECONDS_PER_DAY;
+
+/// Hashes of sources that are known to be formatted.
+///
+/// The first line of the cache file holds the gdfmt version and a hash of the
+/// formatting options, so the whole cache is dropped when either of them
+/// changes. Each hash is stored with the time it was last seen, and hashes not
+/// seen for `MAX_AGE` are dropped, so sources that are gone don't pile up.
+pub struct Cache {
+    path: PathBuf,
+    header: String,
+    now: u64,
+    state: Mutex<CacheState>,
+}
+
+struct CacheState {
+    last_seen: BTreeMap<u64, u64>,
+    is_changed: bool,
+}
+
+impl Cache {
+    pub fn load(path: &Path, config: &Config) -> Self {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs());
+        Self::load_at(path, config, now)
+    }
+
+    fn load_at(path: &Path, config: &Config, now: u64) -> Self {
+        let header = get_header(config);
+        let data = read_to_string(path).unwrap_or_default();
+        let mut lines = data.lines();
+        let entries: Vec<(u64, u64)> = match lines.next() == Some(header.as_str()) {
+            true => lines.filter_map(parse_entry).collect(),
+            false => Vec::new(),
+        };
+        let last_seen: BTreeMap<u64, u64> = entries
+            .iter()
+            .copied()
+            .filter(|(_, time)| time.saturating_add(MAX_AGE) >= now)
+            .collect();
+
+        Self {
+            path: path.to_path_buf(),
+            header,
+            now,
+            state: Mutex::new(CacheState {
+                is_changed: last_seen.len() != entries.len(),
+                last_seen,
+            }),
+        }
+    }
+
+    pub fn contains(&self, file_path: &str, source: &str) -> bool {
+        let hash = get_content_hash(file_path, source);
+        let mut state = self.lock();
+        let Some(time) = state.last_seen.get_mut(&hash) else {
+            return false;
+        };
+        if time.saturating_add(SECONDS_PER_DAY) < self.now {
+            *time = self.now;
+            state.is_changed = true;
+        }
+        true
+    }
+
+    pub fn insert(&self, file_path: &str, source: &str) {
+        let hash = get_content_hash(file_path, source);
+        let mut state = self.lock();
+        if state.last_seen.insert(hash, self.now).is_none() {
+            state.is_changed = true;
+        }
+    }
+
+    /// Writes the cache to a temporary file first and renames it, so an
+    /// interrupted run never leaves a truncated cache behind.
+    pub fn save(&self) -> io::Result<()> {
+        let state = self.lock();
+        if !state.is_changed {
+            return Ok(());
+        }
+        if let Some(dir) = self.path.parent() {
+            create_dir_all(dir)?;
+        }
+
+        let mut output = format!("{}\n", self.header);
+        for (hash, time) in &state.last_seen {
+            output.push_str(&format!("{:016x} {}\n", hash, time));
+        }
+
+        let temp_path = self
+            .path
+            .with_extension(format!("{}.tmp", std::process::id()));
+        write(&temp_path, output)?;
+        rename(&temp_path, &self.path)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, CacheState> {
+        self.state.lock().unwrap_or_else(|error| error.into_inner())
+    }
+}
+
+fn parse_entry(line: &str) -> Option<(u64, u64)> {
+    let (hash, time) = line.split_once(' ')?;
+
+    Some((u64::from_str_radix(hash, 16).ok()?, time.parse().ok()?))
+}
+
+/// The options that only affect linting or where the cache lives are reset, so
+/// changing them keeps the cache.
+fn get_header(config: &Config) -> String {
+    let defaults = Config::default();
+    let formatting_config = Config {
+        max_function_lines: defaults.max_function_lines,
+        max_nesting_depth: defaults.max_nesting_depth,
+        max_parameters: defaults.max_parameters,
+        max_file_lines: defaults.max_file_lines,
+        lint_severity: defaults.lint_severity,
+        cache_path: defaults.cache_path,
+        ..config.clone()
+    };
+    let config_hash = hash_bytes(
+        FNV_OFFSET_BASIS,
+        format!("{:?}", formatting_config).as_bytes(),
+    );
+
+    format!("gdfmt {} {:016x}", env!("CARGO_PKG_VERSION"), config_hash)
+}
+
+fn get_content_hash(file_path: &str, source: &str) -> u64 {
+    let extension = Path::new(file_path)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default();
+    let hash = hash_bytes(FNV_OFFSET_BASIS, extension.as_bytes());
+    let hash = hash_bytes(hash, &[0]);
+
+    hash_bytes(hash, source.as_bytes())
+}
+
+/// 64-bit FNV-1a, which unlike `DefaultHasher` gives the same hashes across
+/// Rust releases.
+fn hash_bytes(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(FNV_PRIME)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    fn get_cache_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir()
+            .join(format!("gdfmt-test-{}", std::process::id()))
+            .join(name);
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[rstest]
+    fn remember_formatted_sources() {
+        let path = get_cache_path("remember");
+        let cache = Cache::load(&path, &Config::default());
+        cache.insert("a.gd", "var a = 1\n");
+        cache.save().unwrap();
+
+        let cache = Cache::load(&path, &Config::default());
+
+        assert!(cache.contains("b.gd", "var a = 1\n"));
+        assert!(!cache.contains("b.gd", "var a = 2\n"));
+        assert!(!cache.contains("b.md", "var a = 1\n"));
+    }
+
+    #[rstest]
+    fn keep_sources_not_seen_in_the_last_run() {
+        let path = get_cache_path("merge");
+        let cache = Cache::load(&path, &Config::default());
+        cache.insert("a.gd", "var a = 1\n");
+        cache.insert("b.gd", "var b = 1\n");
+        cache.save().unwrap();
+
+        let cache = Cache::load(&path, &Config::default());
+        cache.insert("c.gd", "var c = 1\n");
+        cache.save().unwrap();
+
+        let cache = Cache::load(&path, &Config::default());
+
+        assert!(cache.contains("a.gd", "var a = 1\n"));
+        assert!(cache.contains("b.gd", "var b = 1\n"));
+        assert!(cache.contains("c.gd", "var c = 1\n"));
+    }
+
+    #[rstest]
+    fn forget_sources_not_seen_for_too_long() {
+        let path = get_cache_path("prune");
+        let cache = Cache::load_at(&path, &Config::default(), 0);
+        cache.insert("a.gd", "var a = 1\n");
+        cache.insert("b.gd", "var b = 1\n");
+        cache.save().unwrap();
+
+        let cache = Cache::load_at(&path, &Config::default(), MAX_AGE);
+        assert!(cache.contains("a.gd", "var a = 1\n"));
+        cache.save().unwrap();
+
+        let cache = Cache::load_at(&path, &Config::default(), MAX_AGE + 1);
+
+        assert!(cache.contains("a.gd", "var a = 1\n"));
+        assert!(!cache.contains("b.gd", "var b = 1\n"));
+    }
+
+    #[rstest]
+    #[case("", 0xcbf2_9ce4_8422_2325)]
+    #[case("a", 0xaf63_dc4c_8601_ec8c)]
+    #[case("foobar", 0x8594_4171_f739_67e8)]
+    fn hash_with_fnv_1a(#[case] input: &str, #[case] expected_hash: u64) {
+        assert_eq!(
+            hash_bytes(FNV_OFFSET_BASIS, input.as_bytes()),
+            expected_hash
+        );
+    }
+
+    #[rstest]
+    fn drop_cache_when_config_changes() {
+        let path = get_cache_path("config");
+        let cache = Cache::load(&path, &Config::default());
+        cache.insert("a.gd", "var a = 1\n");
+        cache.save().unwrap();
+        let config = Config {
+            line_length: 80,
+            ..Default::default()
+        };
+
+        let cache = Cache::load(&path, &config);
+
+        assert!(!cache.contains("a.gd", "var a = 1\n"));
+    }
+
+    #[rstest]
+    fn keep_cache_when_lint_options_change() {
+        let path = get_cache_path("lint");
+        let cache = Cache::load(&path, &Config::default());
+        cache.insert("a.gd", "var a = 1\n");
+        cache.save().unwrap();
+        let config = Config {
+            max_function_lines: 10,
+            cache_path: Some(".gdfmt_cache".to_string()),
+            ..Default::default()
+        };
+
+        let cache = Cache::load(&path, &config);
+
+        assert!(cache.contains("a.gd", "var a = 1\n"));
+    }
+}
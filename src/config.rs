@@ -0,0 +1,162 @@
+use crate::error::Error;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+pub const CONFIG_FILE_NAME: &str = "gdfmt.toml";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QuoteStyle {
+    Double,
+    Single,
+    Preserve,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HexDigitCase {
+    Lower,
+    Upper,
+    Preserve,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NumberSeparators {
+    Add,
+    Strip,
+    Preserve,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Off,
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Severity::Off => "off",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        f.write_str(name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub keep_empty_signal_parentheses: bool,
+    pub preserve_commented_out_code: bool,
+    pub trailing_comment_spaces: usize,
+    pub align_trailing_comments: bool,
+    pub line_length: usize,
+    pub reflow_doc_comments: bool,
+    pub normalize_triple_quotes: bool,
+    pub quote_style: QuoteStyle,
+    pub minimize_escapes: bool,
+    pub number_separators: NumberSeparators,
+    pub number_separator_threshold: usize,
+    pub decimal_group_length: usize,
+    pub binary_group_length: usize,
+    pub hexadecimal_group_length: usize,
+    pub hex_digit_case: HexDigitCase,
+    pub keep_semicolons: bool,
+    pub keep_short_one_line_blocks: bool,
+    pub reorder_members: bool,
+    pub max_function_lines: usize,
+    pub max_nesting_depth: usize,
+    pub max_parameters: usize,
+    pub max_file_lines: usize,
+    pub lint_severity: BTreeMap<String, Severity>,
+    pub cache_path: Option<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            keep_empty_signal_parentheses: false,
+            preserve_commented_out_code: true,
+            trailing_comment_spaces: 2,
+            align_trailing_comments: false,
+            line_length: 100,
+            reflow_doc_comments: false,
+            normalize_triple_quotes: true,
+            quote_style: QuoteStyle::Double,
+            minimize_escapes: true,
+            number_separators: NumberSeparators::Add,
+            number_separator_threshold: 6,
+            decimal_group_length: 3,
+            binary_group_length: 4,
+            hexadecimal_group_length: 4,
+            hex_digit_case: HexDigitCase::Lower,
+            keep_semicolons: false,
+            keep_short_one_line_blocks: false,
+            reorder_members: false,
+            max_function_lines: 50,
+            max_nesting_depth: 4,
+            max_parameters: 5,
+            max_file_lines: 1000,
+            lint_severity: BTreeMap::new(),
+            cache_path: None,
+        }
+    }
+}
+
+impl Config {
+    pub fn from_toml(text: &str) -> Result<Self, Error> {
+        toml::from_str(text).map_err(|e| Error::InvalidConfig(e.message().to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    fn use_defaults_for_missing_options() {
+        let config = Config::from_toml("").unwrap();
+
+        assert_eq!(config, Config::default());
+    }
+
+    #[rstest]
+    fn read_options_from_toml() {
+        let config = Config::from_toml("keep_empty_signal_parentheses = true").unwrap();
+
+        assert!(config.keep_empty_signal_parentheses);
+    }
+
+    #[rstest]
+    #[case("quote_style = \"double\"", QuoteStyle::Double)]
+    #[case("quote_style = \"single\"", QuoteStyle::Single)]
+    #[case("quote_style = \"preserve\"", QuoteStyle::Preserve)]
+    fn read_quote_style_from_toml(#[case] input: &str, #[case] quote_style: QuoteStyle) {
+        let config = Config::from_toml(input).unwrap();
+
+        assert_eq!(config.quote_style, quote_style);
+    }
+
+    #[rstest]
+    fn read_lint_severities_from_toml() {
+        let config =
+            Config::from_toml("[lint_severity]\nline-length = \"error\"\nfunction-name = \"off\"")
+                .unwrap();
+
+        assert_eq!(config.lint_severity["line-length"], Severity::Error);
+        assert_eq!(config.lint_severity["function-name"], Severity::Off);
+    }
+
+    #[rstest]
+    fn returns_error_on_unknown_options() {
+        let result = Config::from_toml("unknown = true");
+
+        assert!(matches!(result, Err(Error::InvalidConfig(_))));
+    }
+}
@@ -7,4 +7,6 @@ pub enum Error {
     UnableToLoadLanguage(#[from] LanguageError),
     #[error("unable to parse: {0}")]
     UnableToParse(String),
+    #[error("invalid config: {0}")]
+    InvalidConfig(String),
 }
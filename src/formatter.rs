@@ -1,36 +1,139 @@
+use crate::config::Config;
 use crate::error::Error;
 use crate::rules;
-use tree_sitter::Parser;
+use std::cell::RefCell;
+use tree_sitter::{InputEdit, Parser, Tree};
 use tree_sitter_gdscript::LANGUAGE as gdscript_language;
 
+thread_local! {
+    static PARSER: RefCell<Option<Parser>> = const { RefCell::new(None) };
+}
+
+/// Formats GDScript sources with a parser that is kept between calls.
+///
+/// Editors can hold on to the last [`Tree`] and pass it back to
+/// [`Formatter::reparse`] with the edits made to the buffer, so only the changed
+/// parts of the source are parsed again.
+pub struct Formatter {
+    parser: Parser,
+    config: Config,
+}
+
+impl Formatter {
+    pub fn new(config: Config) -> Result<Self, Error> {
+        Ok(Self {
+            parser: new_parser()?,
+            config,
+        })
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    pub fn format(&mut self, source: &str) -> Result<String, Error> {
+        let tree = self.parse(source)?;
+        self.format_tree(&tree, source)
+    }
+
+    /// Parses `source` from scratch. The tree may contain syntax errors.
+    pub fn parse(&mut self, source: &str) -> Result<Tree, Error> {
+        parse_source(&mut self.parser, source, None)
+    }
+
+    /// Applies `edits` to the tree of the previous source and parses `source`
+    /// reusing its unchanged nodes.
+    pub fn reparse(
+        &mut self,
+        source: &str,
+        mut tree: Tree,
+        edits: &[InputEdit],
+    ) -> Result<Tree, Error> {
+        for edit in edits {
+            tree.edit(edit);
+        }
+
+        parse_source(&mut self.parser, source, Some(&tree))
+    }
+
+    /// Formats `source` using a tree parsed from it.
+    pub fn format_tree(&self, tree: &Tree, source: &str) -> Result<String, Error> {
+        check_syntax(tree)?;
+        Ok(format_tree(tree, source, &self.config))
+    }
+}
+
 pub fn format_code(source: &str) -> Result<String, Error> {
+    format_code_with_config(source, &Config::default())
+}
+
+pub fn format_code_with_config(source: &str, config: &Config) -> Result<String, Error> {
+    let tree = parse(source)?;
+    Ok(format_tree(&tree, source, config))
+}
+
+pub(crate) fn parse(source: &str) -> Result<Tree, Error> {
+    let tree = parse_with_errors(source)?;
+    check_syntax(&tree)?;
+
+    Ok(tree)
+}
+
+pub(crate) fn parse_with_errors(source: &str) -> Result<Tree, Error> {
+    PARSER.with_borrow_mut(|parser| {
+        let parser = match parser {
+            Some(parser) => parser,
+            None => parser.insert(new_parser()?),
+        };
+
+        parse_source(parser, source, None)
+    })
+}
+
+fn new_parser() -> Result<Parser, Error> {
     let mut parser = Parser::new();
     parser.set_language(&gdscript_language.into())?;
 
-    let tree = parser.parse(source, None).ok_or_else(|| {
+    Ok(parser)
+}
+
+fn parse_source(parser: &mut Parser, source: &str, old_tree: Option<&Tree>) -> Result<Tree, Error> {
+    parser.parse(source, old_tree).ok_or_else(|| {
         Error::UnableToParse("Internal parser error: Failed to produce syntax tree.".to_string())
-    })?;
-    let root_node = tree.root_node();
-    if root_node.has_error() {
+    })
+}
+
+fn check_syntax(tree: &Tree) -> Result<(), Error> {
+    if tree.root_node().has_error() {
         return Err(Error::UnableToParse(
             "Source code contains syntax errors.".to_string(),
         ));
     }
 
+    Ok(())
+}
+
+fn format_tree(tree: &Tree, source: &str, config: &Config) -> String {
+    let root_node = tree.root_node();
+
     #[cfg(debug_assertions)]
     {
-        println!("{}", "-".repeat(80));
-        print!("{}", crate::debug::node_to_string(root_node, source, 0));
-        println!("{}", "-".repeat(80));
+        eprintln!("{}", "-".repeat(80));
+        eprint!("{}", crate::debug::node_to_string(root_node, source, 0));
+        eprintln!("{}", "-".repeat(80));
     }
 
-    Ok(rules::apply(root_node, source, 0))
+    let mut output = String::with_capacity(source.len());
+    rules::write(root_node, source, config, 0, &mut output);
+
+    output
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use rstest::*;
+    use tree_sitter::Point;
 
     #[rstest]
     fn returns_error_on_syntax_errors() {
@@ -46,4 +149,39 @@ mod tests {
 
         assert_eq!(formatted, expected);
     }
+
+    #[rstest]
+    fn reuse_formatter_between_sources() {
+        let mut formatter = Formatter::new(Config::default()).unwrap();
+
+        assert_eq!(formatter.format("var  a=1").unwrap(), "var a = 1\n");
+        assert_eq!(formatter.format("var  b=2").unwrap(), "var b = 2\n");
+        assert!(matches!(
+            formatter.format("."),
+            Err(Error::UnableToParse(_))
+        ));
+    }
+
+    #[rstest]
+    fn format_reparsed_sources() {
+        let mut formatter = Formatter::new(Config::default()).unwrap();
+        let old_source = "func f():\n\tvar  a=1\n";
+        let new_source = "func f():\n\tvar  abc=1\n";
+        let tree = formatter.parse(old_source).unwrap();
+        let edit = InputEdit {
+            start_byte: 17,
+            old_end_byte: 17,
+            new_end_byte: 19,
+            start_position: Point::new(1, 7),
+            old_end_position: Point::new(1, 7),
+            new_end_position: Point::new(1, 9),
+        };
+
+        let tree = formatter.reparse(new_source, tree, &[edit]).unwrap();
+
+        assert_eq!(
+            formatter.format_tree(&tree, new_source).unwrap(),
+            format_code(new_source).unwrap()
+        );
+    }
 }
@@ -1,10 +1,28 @@
+mod cache;
+mod config;
 #[cfg(debug_assertions)]
 mod debug;
 mod error;
 mod formatter;
+mod lint;
+mod markdown;
+mod migrate;
 mod node;
+mod report;
+mod resource;
 mod rules;
 mod text;
 
+pub use cache::{CACHE_DIR_NAME, CACHE_FILE_NAME, Cache};
+pub use config::{CONFIG_FILE_NAME, Config, HexDigitCase, NumberSeparators, QuoteStyle, Severity};
 pub use error::Error;
-pub use formatter::format_code;
+pub use formatter::{Formatter, format_code, format_code_with_config};
+pub use lint::{
+    Diagnostic, FILE_ERROR_RULE, RULES, SYNTAX_ERROR_RULE, UNFORMATTED_RULE, find_syntax_error,
+    lint_code,
+};
+pub use markdown::{CodeBlockError, MARKDOWN_EXTENSIONS, MarkdownFormat, format_markdown};
+pub use migrate::{Migration, MigrationIssue, migrate_code};
+pub use report::{FileReport, FileStatus, to_checkstyle, to_json, to_sarif};
+pub use resource::{RESOURCE_EXTENSIONS, ResourceFormat, format_resource};
+pub use tree_sitter::{InputEdit, Point, Tree};
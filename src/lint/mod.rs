@@ -0,0 +1,174 @@
+mod naming;
+mod structure;
+
+use crate::config::{Config, Severity};
+use crate::error::Error;
+use crate::formatter::{parse, parse_with_errors};
+use crate::node::get_column;
+use serde::Serialize;
+use std::fmt;
+use tree_sitter::Node;
+
+pub const SYNTAX_ERROR_RULE: &str = "syntax-error";
+pub const FILE_ERROR_RULE: &str = "file-error";
+pub const UNFORMATTED_RULE: &str = "unformatted";
+pub const RULES: [(&str, Severity); 18] = [
+    (SYNTAX_ERROR_RULE, Severity::Error),
+    (FILE_ERROR_RULE, Severity::Error),
+    (UNFORMATTED_RULE, Severity::Error),
+    ("function-name", Severity::Warning),
+    ("variable-name", Severity::Warning),
+    ("signal-name", Severity::Warning),
+    ("class-name", Severity::Warning),
+    ("enum-name", Severity::Warning),
+    ("constant-name", Severity::Warning),
+    ("enum-member-name", Severity::Warning),
+    ("node-name", Severity::Warning),
+    ("virtual-method-prefix", Severity::Warning),
+    ("private-member-access", Severity::Warning),
+    ("function-length", Severity::Warning),
+    ("nesting-depth", Severity::Warning),
+    ("parameter-count", Severity::Warning),
+    ("file-length", Severity::Warning),
+    ("line-length", Severity::Warning),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Diagnostic {
+    pub rule: &'static str,
+    pub severity: Severity,
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl Diagnostic {
+    fn new(rule: &'static str, node: Node, source: &str, message: String) -> Self {
+        let line = node.start_position().row + 1;
+        Self::at(rule, line, get_column(node, source), message)
+    }
+
+    pub fn at(rule: &'static str, line: usize, column: usize, message: String) -> Self {
+        let severity = RULES
+            .iter()
+            .find(|(id, _)| *id == rule)
+            .map_or(Severity::Warning, |(_, severity)| *severity);
+        Self {
+            rule,
+            severity,
+            line,
+            column,
+            message,
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}: {}: {} [{}]",
+            self.line, self.column, self.severity, self.message, self.rule
+        )
+    }
+}
+
+pub fn lint_code(source: &str, config: &Config) -> Result<Vec<Diagnostic>, Error> {
+    let tree = parse(source)?;
+    let mut diagnostics = Vec::new();
+
+    walk(tree.root_node(), source, config, &mut diagnostics);
+    structure::check_lines(source, config, &mut diagnostics);
+
+    for diagnostic in &mut diagnostics {
+        if let Some(severity) = config.lint_severity.get(diagnostic.rule) {
+            diagnostic.severity = *severity;
+        }
+    }
+    diagnostics.retain(|diagnostic| diagnostic.severity != Severity::Off);
+    diagnostics.sort_by_key(|diagnostic| (diagnostic.line, diagnostic.column));
+
+    Ok(diagnostics)
+}
+
+pub fn find_syntax_error(source: &str) -> Option<Diagnostic> {
+    let tree = parse_with_errors(source).ok()?;
+    let root_node = tree.root_node();
+    if !root_node.has_error() {
+        return None;
+    }
+
+    let mut node = root_node;
+    while let Some(child) = node
+        .children(&mut node.walk())
+        .find(|child| child.has_error())
+    {
+        node = child;
+    }
+    let message = if node.is_missing() {
+        format!("missing `{}`", node.kind())
+    } else {
+        "syntax error".to_string()
+    };
+
+    Some(Diagnostic::new(SYNTAX_ERROR_RULE, node, source, message))
+}
+
+/// Visits every node. The formatting walk in `rules::write` can't be reused
+/// because it copies most expressions as text without visiting their children,
+/// so names inside calls, lambdas or `$Node` paths would be missed.
+fn walk(node: Node, source: &str, config: &Config, diagnostics: &mut Vec<Diagnostic>) {
+    naming::check(node, source, diagnostics);
+    structure::check(node, source, config, diagnostics);
+
+    for child in node.children(&mut node.walk()) {
+        walk(child, source, config, diagnostics);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    fn use_configured_severities() {
+        let mut config = Config::default();
+        config
+            .lint_severity
+            .insert("function-name".to_string(), Severity::Error);
+        config
+            .lint_severity
+            .insert("variable-name".to_string(), Severity::Off);
+
+        let diagnostics = lint_code("var myVar\nfunc doIt():\n\tpass", &config).unwrap();
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            (diagnostics[0].rule, diagnostics[0].severity),
+            ("function-name", Severity::Error)
+        );
+    }
+
+    #[rstest]
+    #[case("var a = 1\nvar = 2", 2, 4)]
+    #[case("func f():\n\tprint(a", 2, 2)]
+    fn find_syntax_errors(#[case] source_input: &str, #[case] line: usize, #[case] column: usize) {
+        let diagnostic = find_syntax_error(source_input).unwrap();
+
+        assert_eq!(
+            (diagnostic.rule, diagnostic.line, diagnostic.column),
+            (SYNTAX_ERROR_RULE, line, column)
+        );
+    }
+
+    #[rstest]
+    fn display_diagnostics() {
+        let diagnostics = lint_code("var myVar", &Config::default()).unwrap();
+
+        assert_eq!(
+            diagnostics[0].to_string(),
+            "1:5: warning: `myVar` should be written in snake_case [variable-name]"
+        );
+    }
+}
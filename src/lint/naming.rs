@@ -0,0 +1,249 @@
+use super::Diagnostic;
+use crate::node::{VIRTUAL_METHODS, get_node_text};
+use tree_sitter::Node;
+
+/// Object methods that share a name with a virtual method minus its prefix, so
+/// defining them is not a missing `_`.
+const OBJECT_METHODS: [&str; 3] = ["get", "set", "to_string"];
+const NODE_PATH_PREFIXES: [char; 2] = ['$', '%'];
+const NODE_PATH_QUOTES: [char; 2] = ['"', '\''];
+/// Objects through which a script reaches its own `_` members.
+const OWN_OBJECTS: [&str; 2] = ["self", "super"];
+
+#[derive(Clone, Copy)]
+enum Case {
+    Snake,
+    Pascal,
+    Constant,
+}
+
+impl Case {
+    fn name(self) -> &'static str {
+        match self {
+            Case::Snake => "snake_case",
+            Case::Pascal => "PascalCase",
+            Case::Constant => "CONSTANT_CASE",
+        }
+    }
+
+    fn matches(self, name: &str) -> bool {
+        let name = name.trim_start_matches('_');
+        let Some(first) = name.chars().next() else {
+            return true;
+        };
+        match self {
+            Case::Snake => {
+                first.is_ascii_lowercase()
+                    && name
+                        .chars()
+                        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+            }
+            Case::Pascal => {
+                first.is_ascii_uppercase() && name.chars().all(|c| c.is_ascii_alphanumeric())
+            }
+            Case::Constant => {
+                first.is_ascii_uppercase()
+                    && name
+                        .chars()
+                        .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+            }
+        }
+    }
+}
+
+pub fn check(node: Node, source: &str, diagnostics: &mut Vec<Diagnostic>) {
+    let name = get_name(node);
+    let (rule, case, name) = match (node.kind(), name) {
+        ("function_definition", Some(name)) => {
+            check_virtual_method(name, source, diagnostics);
+            ("function-name", Case::Snake, name)
+        }
+        (
+            "variable_statement" | "export_variable_statement" | "onready_variable_statement",
+            Some(name),
+        ) => ("variable-name", Case::Snake, name),
+        ("signal_statement", Some(name)) => ("signal-name", Case::Snake, name),
+        ("class_name_statement" | "class_definition", Some(name)) => {
+            ("class-name", Case::Pascal, name)
+        }
+        ("enum_definition", Some(name)) => ("enum-name", Case::Pascal, name),
+        ("const_statement", Some(name)) => ("constant-name", Case::Constant, name),
+        ("enumerator", _) => match node.named_child(0) {
+            Some(name) => ("enum-member-name", Case::Constant, name),
+            None => return,
+        },
+        ("get_node", _) => return check_node_path(node, source, diagnostics),
+        ("attribute", _) => return check_private_access(node, source, diagnostics),
+        _ => return,
+    };
+
+    let text = get_node_text(name, source);
+    if !case.matches(text) {
+        diagnostics.push(Diagnostic::new(
+            rule,
+            name,
+            source,
+            format!("`{}` should be written in {}", text, case.name()),
+        ));
+    }
+}
+
+fn get_name(node: Node) -> Option<Node> {
+    node.child_by_field_name("name").or_else(|| {
+        node.children(&mut node.walk())
+            .find(|child| child.kind() == "name")
+    })
+}
+
+/// Virtual methods must keep their `_` prefix. Whether any other member should
+/// have it depends on how it is used, which `check_private_access` covers.
+fn check_virtual_method(name: Node, source: &str, diagnostics: &mut Vec<Diagnostic>) {
+    let text = get_node_text(name, source);
+    if OBJECT_METHODS.contains(&text) {
+        return;
+    }
+    if let Some(method) = VIRTUAL_METHODS.iter().find(|method| &method[1..] == text) {
+        diagnostics.push(Diagnostic::new(
+            "virtual-method-prefix",
+            name,
+            source,
+            format!("`{}` looks like the virtual method `{}`", text, method),
+        ));
+    }
+}
+
+/// A `_` member belongs to the script that declares it, so it is only reached
+/// through `self` or `super`. Virtual methods are left out, since engine
+/// callbacks like `_input` are sometimes forwarded to other objects.
+fn check_private_access(node: Node, source: &str, diagnostics: &mut Vec<Diagnostic>) {
+    let children: Vec<Node> = node.children(&mut node.walk()).collect();
+    let members = children
+        .iter()
+        .enumerate()
+        .skip(1)
+        .filter(|(index, _)| children[index - 1].kind() == ".");
+
+    for (index, member) in members {
+        let is_own_member = index == 2 && OWN_OBJECTS.contains(&get_node_text(children[0], source));
+        let name = match member.kind() {
+            "attribute_call" | "attribute_subscript" => member.named_child(0),
+            _ => Some(*member),
+        };
+        let Some(name) = name.filter(|_| !is_own_member) else {
+            continue;
+        };
+
+        let text = get_node_text(name, source);
+        if text.starts_with('_') && !VIRTUAL_METHODS.contains(&text) {
+            diagnostics.push(Diagnostic::new(
+                "private-member-access",
+                name,
+                source,
+                format!("`{}` is private to the script that declares it", text),
+            ));
+        }
+    }
+}
+
+fn check_node_path(node: Node, source: &str, diagnostics: &mut Vec<Diagnostic>) {
+    let path = get_node_text(node, source)
+        .trim_start_matches(NODE_PATH_PREFIXES)
+        .trim_matches(NODE_PATH_QUOTES);
+    let invalid_name = path
+        .split('/')
+        .map(|name| name.trim_start_matches('%'))
+        .filter(|name| !matches!(*name, "" | "." | ".."))
+        .find(|name| !Case::Pascal.matches(name));
+
+    if let Some(name) = invalid_name {
+        diagnostics.push(Diagnostic::new(
+            "node-name",
+            node,
+            source,
+            format!(
+                "node name `{}` should be written in {}",
+                name,
+                Case::Pascal.name()
+            ),
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Config, lint_code};
+    use rstest::*;
+
+    #[rstest]
+    #[case("func doIt():\n\tpass", "function-name", 1, 6)]
+    #[case("var myVar = 1", "variable-name", 1, 5)]
+    #[case("func f():\n\tvar LocalVar = 1", "variable-name", 2, 6)]
+    #[case("@export var Speed = 1", "variable-name", 1, 13)]
+    #[case("signal DoThing", "signal-name", 1, 8)]
+    #[case("class_name my_class", "class-name", 1, 12)]
+    #[case("class inner:\n\tpass", "class-name", 1, 7)]
+    #[case("enum state { IDLE }", "enum-name", 1, 6)]
+    #[case("const maxSpeed = 1", "constant-name", 1, 7)]
+    #[case("enum State { idle }", "enum-member-name", 1, 14)]
+    #[case("func f():\n\tvar a = $Path/to_node", "node-name", 2, 10)]
+    #[case("func f():\n\tvar a = %my_node", "node-name", 2, 10)]
+    #[case("func ready():\n\tpass", "virtual-method-prefix", 1, 6)]
+    #[case("func physics_process(delta):\n\tpass", "virtual-method-prefix", 1, 6)]
+    #[case("func input(event):\n\tpass", "virtual-method-prefix", 1, 6)]
+    #[case("func draw():\n\tpass", "virtual-method-prefix", 1, 6)]
+    #[case("func notification(what):\n\tpass", "virtual-method-prefix", 1, 6)]
+    #[case("var a = \"ééé\"; var myVar = 1", "variable-name", 1, 20)]
+    #[case("func f():\n\tplayer._health = 0", "private-member-access", 2, 9)]
+    #[case("func f():\n\tplayer._hit(1)", "private-member-access", 2, 9)]
+    #[case(
+        "func f():\n\tvar a = player._items[0]",
+        "private-member-access",
+        2,
+        17
+    )]
+    #[case("func f():\n\tself.player._hit()", "private-member-access", 2, 14)]
+    fn report_naming_violations(
+        #[case] source_input: &str,
+        #[case] rule: &str,
+        #[case] line: usize,
+        #[case] column: usize,
+    ) {
+        let diagnostics = lint_code(source_input, &Config::default()).unwrap();
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            (
+                diagnostics[0].rule,
+                diagnostics[0].line,
+                diagnostics[0].column
+            ),
+            (rule, line, column)
+        );
+    }
+
+    #[rstest]
+    #[case("func _ready():\n\tvar _unused = 1")]
+    #[case("func get(key):\n\tpass")]
+    #[case("func to_string():\n\treturn \"\"")]
+    #[case("var speed_2d = 1\nvar _private_value = 2")]
+    #[case("signal health_changed(old_value, new_value)")]
+    #[case("class_name Player\nclass StateMachine:\n\tpass")]
+    #[case("const MAX_SPEED = 1\nenum Element { EARTH, WATER_2 }")]
+    #[case("func f():\n\tvar a = $UI/HealthBar\n\tvar b = $\"../Sibling\"\n\tvar c = %Unique")]
+    #[case("func f():\n\tself._health = 0\n\tsuper._ready()\n\t_items.clear()")]
+    #[case("func _input(event):\n\tchild._input(event)")]
+    fn accept_style_guide_names(#[case] source_input: &str) {
+        let diagnostics = lint_code(source_input, &Config::default()).unwrap();
+
+        assert_eq!(diagnostics, []);
+    }
+
+    #[rstest]
+    fn accept_sample_styleguide() {
+        let source = std::fs::read_to_string("samples/styleguide.gd").unwrap();
+
+        let diagnostics = lint_code(&source, &Config::default()).unwrap();
+
+        assert_eq!(diagnostics, []);
+    }
+}
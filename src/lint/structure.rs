@@ -0,0 +1,231 @@
+use super::Diagnostic;
+use crate::config::Config;
+use crate::text::{TAB_WIDTH, line_width};
+use tree_sitter::Node;
+
+const FUNCTION_KINDS: [&str; 3] = ["function_definition", "constructor_definition", "lambda"];
+const BLOCK_KINDS: [&str; 4] = [
+    "if_statement",
+    "for_statement",
+    "while_statement",
+    "match_statement",
+];
+
+pub fn check(node: Node, source: &str, config: &Config, diagnostics: &mut Vec<Diagnostic>) {
+    match node.kind() {
+        "function_definition" | "constructor_definition" => {
+            check_function_length(node, source, config, diagnostics);
+            check_parameter_count(node, source, config, diagnostics);
+        }
+        kind if BLOCK_KINDS.contains(&kind) => {
+            check_nesting_depth(node, source, config, diagnostics)
+        }
+        _ => {}
+    }
+}
+
+pub fn check_lines(source: &str, config: &Config, diagnostics: &mut Vec<Diagnostic>) {
+    let line_count = source.lines().count();
+    if line_count > config.max_file_lines {
+        diagnostics.push(Diagnostic::at(
+            "file-length",
+            config.max_file_lines + 1,
+            1,
+            format!(
+                "file has {} lines, more than the maximum of {}",
+                line_count, config.max_file_lines
+            ),
+        ));
+    }
+
+    for (index, line) in source.lines().enumerate() {
+        let width = line_width(line);
+        if width > config.line_length {
+            diagnostics.push(Diagnostic::at(
+                "line-length",
+                index + 1,
+                get_overflow_column(line, config.line_length),
+                format!(
+                    "line is {} columns wide, more than the maximum of {}",
+                    width, config.line_length
+                ),
+            ));
+        }
+    }
+}
+
+fn check_function_length(
+    node: Node,
+    source: &str,
+    config: &Config,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let line_count = node.end_position().row - node.start_position().row + 1;
+    if line_count > config.max_function_lines {
+        diagnostics.push(Diagnostic::new(
+            "function-length",
+            node,
+            source,
+            format!(
+                "function has {} lines, more than the maximum of {}",
+                line_count, config.max_function_lines
+            ),
+        ));
+    }
+}
+
+fn check_parameter_count(
+    node: Node,
+    source: &str,
+    config: &Config,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let Some(parameters) = node.child_by_field_name("parameters") else {
+        return;
+    };
+    let count = parameters.named_child_count();
+    if count > config.max_parameters {
+        diagnostics.push(Diagnostic::new(
+            "parameter-count",
+            parameters,
+            source,
+            format!(
+                "function has {} parameters, more than the maximum of {}",
+                count, config.max_parameters
+            ),
+        ));
+    }
+}
+
+fn check_nesting_depth(
+    node: Node,
+    source: &str,
+    config: &Config,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    if get_nesting_depth(node) == config.max_nesting_depth + 1 {
+        diagnostics.push(Diagnostic::new(
+            "nesting-depth",
+            node,
+            source,
+            format!(
+                "block is nested deeper than the maximum of {}",
+                config.max_nesting_depth
+            ),
+        ));
+    }
+}
+
+fn get_nesting_depth(node: Node) -> usize {
+    let mut depth = 1;
+    let mut parent = node.parent();
+
+    while let Some(ancestor) = parent.filter(|n| !FUNCTION_KINDS.contains(&n.kind())) {
+        if BLOCK_KINDS.contains(&ancestor.kind()) {
+            depth += 1;
+        }
+        parent = ancestor.parent();
+    }
+
+    depth
+}
+
+fn get_overflow_column(line: &str, line_length: usize) -> usize {
+    let mut width = 0;
+
+    for (index, c) in line.chars().enumerate() {
+        width += if c == '\t' { TAB_WIDTH } else { 1 };
+        if width > line_length {
+            return index + 1;
+        }
+    }
+
+    1
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Config, lint_code};
+    use rstest::*;
+
+    fn get_rules(source: &str, config: &Config) -> Vec<(&'static str, usize, usize)> {
+        lint_code(source, config)
+            .unwrap()
+            .iter()
+            .map(|diagnostic| (diagnostic.rule, diagnostic.line, diagnostic.column))
+            .collect()
+    }
+
+    #[rstest]
+    #[case("func f():\n\tpass\n\tpass", vec![])]
+    #[case("func f():\n\tpass\n\tpass\n\tpass", vec![("function-length", 1, 1)])]
+    #[case(
+        "class A:\n\tfunc _init():\n\t\tpass\n\t\tpass\n\t\tpass",
+        vec![("function-length", 2, 2)]
+    )]
+    fn report_long_functions(
+        #[case] source_input: &str,
+        #[case] expected: Vec<(&'static str, usize, usize)>,
+    ) {
+        let config = Config {
+            max_function_lines: 3,
+            ..Default::default()
+        };
+
+        assert_eq!(get_rules(source_input, &config), expected);
+    }
+
+    #[rstest]
+    #[case("func f():\n\tif a:\n\t\tfor x in xs:\n\t\t\tpass", vec![])]
+    #[case(
+        "func f():\n\tif a:\n\t\tfor x in xs:\n\t\t\twhile b:\n\t\t\t\tif c:\n\t\t\t\t\tpass",
+        vec![("nesting-depth", 4, 4)]
+    )]
+    #[case(
+        "func f():\n\tif a:\n\t\tpass\n\telse:\n\t\tmatch x:\n\t\t\t1:\n\t\t\t\tif b:\n\t\t\t\t\tpass",
+        vec![("nesting-depth", 7, 5)]
+    )]
+    fn report_deeply_nested_blocks(
+        #[case] source_input: &str,
+        #[case] expected: Vec<(&'static str, usize, usize)>,
+    ) {
+        let config = Config {
+            max_nesting_depth: 2,
+            ..Default::default()
+        };
+
+        assert_eq!(get_rules(source_input, &config), expected);
+    }
+
+    #[rstest]
+    #[case("func f(a, b):\n\tpass", vec![])]
+    #[case("func f(a, b: int, c = 1):\n\tpass", vec![("parameter-count", 1, 7)])]
+    fn report_functions_with_many_parameters(
+        #[case] source_input: &str,
+        #[case] expected: Vec<(&'static str, usize, usize)>,
+    ) {
+        let config = Config {
+            max_parameters: 2,
+            ..Default::default()
+        };
+
+        assert_eq!(get_rules(source_input, &config), expected);
+    }
+
+    #[rstest]
+    #[case("var a\nvar b", vec![])]
+    #[case("var a\nvar b\nvar c", vec![("file-length", 3, 1)])]
+    #[case("func f():\n\tvar bb = 1", vec![("line-length", 2, 7)])]
+    fn report_long_files_and_lines(
+        #[case] source_input: &str,
+        #[case] expected: Vec<(&'static str, usize, usize)>,
+    ) {
+        let config = Config {
+            max_file_lines: 2,
+            line_length: 9,
+            ..Default::default()
+        };
+
+        assert_eq!(get_rules(source_input, &config), expected);
+    }
+}
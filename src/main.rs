@@ -1,30 +1,438 @@
-use anyhow::{Context, Result};
-use gdfmt::format_code;
+use anyhow::{Context, Result, bail};
+use gdfmt::{
+    CACHE_DIR_NAME, CACHE_FILE_NAME, CONFIG_FILE_NAME, Cache, Config, Diagnostic, FileReport,
+    FileStatus, MARKDOWN_EXTENSIONS, RESOURCE_EXTENSIONS, SYNTAX_ERROR_RULE, Severity,
+    UNFORMATTED_RULE, find_syntax_error, format_code_with_config, format_markdown, format_resource,
+    lint_code, migrate_code, to_checkstyle, to_json, to_sarif,
+};
 use std::{
+    any::Any,
     fs::{read_to_string, write},
     io::Read,
+    num::NonZeroUsize,
+    panic::{AssertUnwindSafe, catch_unwind},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+    time::{Duration, Instant},
 };
 
+const CHECK_COMMAND: &str = "check";
+const LINT_COMMAND: &str = "lint";
+const MIGRATE_COMMAND: &str = "migrate";
+const FORMAT_OPTION: &str = "--format";
+const JOBS_OPTION: &str = "--jobs";
+const NO_CACHE_OPTION: &str = "--no-cache";
+const STDIN_NAME: &str = "<stdin>";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Command {
+    Format,
+    Check,
+    Lint,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ReportFormat {
+    Text,
+    Json,
+    Sarif,
+    Checkstyle,
+}
+
+struct Options {
+    command: Command,
+    report_format: ReportFormat,
+    jobs: NonZeroUsize,
+    use_cache: bool,
+    file_paths: Vec<String>,
+}
+
 fn main() -> Result<()> {
-    let mut source = String::new();
-    let args: Vec<String> = std::env::args().collect();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    if args.first().map(String::as_str) == Some(MIGRATE_COMMAND) {
+        return migrate_files(&args[1..]);
+    }
+
+    let options = parse_options(&args)?;
+    let config = load_config()?;
+
+    if options.command == Command::Format && options.file_paths.is_empty() {
+        if options.report_format != ReportFormat::Text {
+            bail!("Formatting stdin only supports the text report format.");
+        }
+        let source = read_source(None)?;
+        let output = format_code_with_config(&source, &config)?;
+        print!("{}", output);
+        return Ok(());
+    }
+
+    let file_paths: Vec<Option<&String>> = match options.file_paths.as_slice() {
+        [] => vec![None],
+        file_paths => file_paths.iter().map(Some).collect(),
+    };
+    let cache = load_cache(&options, &config)?;
+    let reports = process_files(
+        &file_paths,
+        options.command,
+        &config,
+        cache.as_ref(),
+        options.jobs,
+    );
+    if let Some(cache) = &cache
+        && let Err(error) = cache.save()
+    {
+        eprintln!("Unable to write cache file: {}", error);
+    }
+
+    match options.report_format {
+        ReportFormat::Text => print_text_report(&reports, options.command),
+        ReportFormat::Json => println!("{}", to_json(&reports)),
+        ReportFormat::Sarif => println!("{}", to_sarif(&reports, &get_project_root()?)),
+        ReportFormat::Checkstyle => print!("{}", to_checkstyle(&reports, &get_project_root()?)),
+    }
+
+    if !is_success(&reports, options.command) {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+fn parse_options(args: &[String]) -> Result<Options> {
+    let (command, mut args) = match args.first().map(String::as_str) {
+        Some(CHECK_COMMAND) => (Command::Check, &args[1..]),
+        Some(LINT_COMMAND) => (Command::Lint, &args[1..]),
+        _ => (Command::Format, args),
+    };
+    let mut options = Options {
+        command,
+        report_format: ReportFormat::Text,
+        jobs: thread::available_parallelism().unwrap_or(NonZeroUsize::MIN),
+        use_cache: true,
+        file_paths: Vec::new(),
+    };
+
+    while let Some((arg, rest)) = args.split_first() {
+        args = rest;
+        if arg == NO_CACHE_OPTION {
+            options.use_cache = false;
+            continue;
+        }
+        if arg != FORMAT_OPTION && arg != JOBS_OPTION {
+            options.file_paths.push(arg.clone());
+            continue;
+        }
+        let Some((value, rest)) = args.split_first() else {
+            bail!("Missing value for {}.", arg);
+        };
+        args = rest;
+        if arg == JOBS_OPTION {
+            options.jobs = value
+                .parse()
+                .with_context(|| format!("Invalid number of jobs: {}", value))?;
+            continue;
+        }
+        options.report_format = match value.as_str() {
+            "text" => ReportFormat::Text,
+            "json" => ReportFormat::Json,
+            "sarif" => ReportFormat::Sarif,
+            "checkstyle" => ReportFormat::Checkstyle,
+            _ => bail!("Unknown report format: {}", value),
+        };
+    }
+
+    Ok(options)
+}
+
+fn process_files(
+    file_paths: &[Option<&String>],
+    command: Command,
+    config: &Config,
+    cache: Option<&Cache>,
+    jobs: NonZeroUsize,
+) -> Vec<FileReport> {
+    let next_index = AtomicUsize::new(0);
+    let worker_count = jobs.get().min(file_paths.len());
+
+    let mut reports: Vec<(usize, FileReport)> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..worker_count)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut reports = Vec::new();
+                    loop {
+                        let index = next_index.fetch_add(1, Ordering::Relaxed);
+                        let Some(file_path) = file_paths.get(index) else {
+                            break;
+                        };
+                        let report = catch_unwind(AssertUnwindSafe(|| {
+                            process_file(*file_path, command, config, cache)
+                        }))
+                        .unwrap_or_else(|panic| get_panic_report(*file_path, panic));
+                        reports.push((index, report));
+                    }
+                    reports
+                })
+            })
+            .collect();
+
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("formatting worker panicked"))
+            .collect()
+    });
+    reports.sort_by_key(|(index, _)| *index);
+
+    reports.into_iter().map(|(_, report)| report).collect()
+}
 
-    if let Some(file_path) = args.get(1) {
-        let data = read_to_string(file_path)
-            .with_context(|| format!("Unable to read from file: {}", file_path))?;
-        source.push_str(&data);
+/// A bug in one rule shouldn't stop the other files from being processed, so a
+/// panic is reported as an error for the file that caused it.
+fn get_panic_report(file_path: Option<&String>, panic: Box<dyn Any + Send>) -> FileReport {
+    let message = panic
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| panic.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown error".to_string());
+
+    FileReport {
+        path: file_path.map_or(STDIN_NAME, String::as_str).to_string(),
+        status: FileStatus::Error,
+        error: Some(format!("gdfmt crashed: {}", message)),
+        diagnostics: Vec::new(),
+        duration: Duration::ZERO,
+    }
+}
+
+fn process_file(
+    file_path: Option<&String>,
+    command: Command,
+    config: &Config,
+    cache: Option<&Cache>,
+) -> FileReport {
+    let start = Instant::now();
+    let mut report = FileReport {
+        path: file_path.map_or(STDIN_NAME, String::as_str).to_string(),
+        status: FileStatus::Unchanged,
+        error: None,
+        diagnostics: Vec::new(),
+        duration: Duration::ZERO,
+    };
+
+    let source = match read_source(file_path) {
+        Ok(source) => source,
+        Err(error) => {
+            report.status = FileStatus::Error;
+            report.error = Some(format!("{:#}", error));
+            report.duration = start.elapsed();
+            return report;
+        }
+    };
+
+    match run_command(file_path, &report.path, &source, command, config, cache) {
+        Ok((status, diagnostics)) => {
+            report.status = status;
+            report.diagnostics = diagnostics;
+        }
+        Err(error) => {
+            report.status = FileStatus::Error;
+            report.error = Some(format!("{:#}", error));
+            if !is_embedded(&report.path) {
+                report.diagnostics.extend(find_syntax_error(&source));
+            }
+        }
+    }
+    report.duration = start.elapsed();
+
+    report
+}
+
+fn run_command(
+    file_path: Option<&String>,
+    name: &str,
+    source: &str,
+    command: Command,
+    config: &Config,
+    cache: Option<&Cache>,
+) -> Result<(FileStatus, Vec<Diagnostic>)> {
+    if command == Command::Lint {
+        return Ok((FileStatus::Unchanged, lint_code(source, config)?));
+    }
+    if cache.is_some_and(|cache| cache.contains(name, source)) {
+        return Ok((FileStatus::Unchanged, Vec::new()));
+    }
 
-        let output = format_code(&source)?;
-        write(file_path, output)
+    let (output, mut diagnostics) = format_file(name, source, config)?;
+    let cache = cache.filter(|_| diagnostics.is_empty());
+    if output == source {
+        if let Some(cache) = cache {
+            cache.insert(name, source);
+        }
+        return Ok((FileStatus::Unchanged, diagnostics));
+    }
+    if command == Command::Check {
+        let message = "file would be reformatted".to_string();
+        diagnostics.push(Diagnostic::at(UNFORMATTED_RULE, 1, 1, message));
+    }
+    if let Some(file_path) = file_path.filter(|_| command == Command::Format) {
+        write(file_path, &output)
             .with_context(|| format!("Unable to write to file: {}", file_path))?;
+        if let Some(cache) = cache.filter(|_| is_fixpoint(name, &output, config)) {
+            cache.insert(name, &output);
+        }
+    }
+
+    Ok((FileStatus::Formatted, diagnostics))
+}
+
+/// Formatted output is only cached when formatting it again changes nothing,
+/// otherwise the next run would skip a file that is not formatted yet.
+fn is_fixpoint(name: &str, output: &str, config: &Config) -> bool {
+    format_file(name, output, config)
+        .is_ok_and(|(reformatted, diagnostics)| reformatted == output && diagnostics.is_empty())
+}
+
+fn format_file(
+    file_path: &str,
+    source: &str,
+    config: &Config,
+) -> Result<(String, Vec<Diagnostic>), gdfmt::Error> {
+    let (code, errors) = if has_extension(file_path, &RESOURCE_EXTENSIONS) {
+        let formatted = format_resource(source, config);
+        (formatted.code, formatted.errors)
+    } else if has_extension(file_path, &MARKDOWN_EXTENSIONS) {
+        let formatted = format_markdown(source, config);
+        (formatted.code, formatted.errors)
     } else {
-        std::io::stdin()
-            .read_to_string(&mut source)
-            .context("Unable to read from stdin.")?;
+        return Ok((format_code_with_config(source, config)?, Vec::new()));
+    };
+    let diagnostics = errors
+        .into_iter()
+        .map(|error| Diagnostic::at(SYNTAX_ERROR_RULE, error.line, 1, error.message))
+        .collect();
 
-        let output = format_code(&source)?;
-        print!("{}", output);
+    Ok((code, diagnostics))
+}
+
+fn print_text_report(reports: &[FileReport], command: Command) {
+    for report in reports {
+        if let Some(error) = &report.error {
+            eprintln!("{}: {}", report.path, error);
+        }
+        for diagnostic in &report.diagnostics {
+            match command {
+                Command::Check | Command::Lint => println!("{}:{}", report.path, diagnostic),
+                Command::Format => eprintln!("{}:{}", report.path, diagnostic),
+            }
+        }
+    }
+}
+
+fn is_success(reports: &[FileReport], command: Command) -> bool {
+    reports.iter().all(|report| {
+        let is_status_ok = match command {
+            Command::Check => report.status == FileStatus::Unchanged,
+            Command::Format | Command::Lint => report.status != FileStatus::Error,
+        };
+        is_status_ok
+            && report
+                .diagnostics
+                .iter()
+                .all(|d| d.severity != Severity::Error)
+    })
+}
+
+fn migrate_files(file_paths: &[String]) -> Result<()> {
+    match file_paths {
+        [] => migrate(None),
+        file_paths => file_paths.iter().try_for_each(|path| migrate(Some(path))),
+    }
+}
+
+fn migrate(file_path: Option<&String>) -> Result<()> {
+    let source = read_source(file_path)?;
+    let migration = migrate_code(&source)?;
+    let name = file_path.map_or(STDIN_NAME, String::as_str);
+    for issue in &migration.issues {
+        eprintln!("{}:{}", name, issue);
+    }
+
+    match file_path {
+        Some(file_path) => write(file_path, migration.code)
+            .with_context(|| format!("Unable to write to file: {}", file_path))?,
+        None => print!("{}", migration.code),
     }
 
     Ok(())
 }
+
+fn read_source(file_path: Option<&String>) -> Result<String> {
+    match file_path {
+        Some(file_path) => read_to_string(file_path)
+            .with_context(|| format!("Unable to read from file: {}", file_path)),
+        None => {
+            let mut source = String::new();
+            std::io::stdin()
+                .read_to_string(&mut source)
+                .context("Unable to read from stdin.")?;
+            Ok(source)
+        }
+    }
+}
+
+fn is_embedded(file_path: &str) -> bool {
+    has_extension(file_path, &RESOURCE_EXTENSIONS) || has_extension(file_path, &MARKDOWN_EXTENSIONS)
+}
+
+fn has_extension(file_path: &str, extensions: &[&str]) -> bool {
+    Path::new(file_path)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| extensions.contains(&extension))
+}
+
+fn load_config() -> Result<Config> {
+    let Some(config_path) = find_config_file() else {
+        return Ok(Config::default());
+    };
+    let data = read_to_string(&config_path)
+        .with_context(|| format!("Unable to read config file: {}", config_path.display()))?;
+
+    Config::from_toml(&data)
+        .with_context(|| format!("Unable to load config file: {}", config_path.display()))
+}
+
+fn load_cache(options: &Options, config: &Config) -> Result<Option<Cache>> {
+    if !options.use_cache || options.command == Command::Lint {
+        return Ok(None);
+    }
+
+    let root = get_project_root()?;
+    let cache_path = match &config.cache_path {
+        Some(cache_path) => root.join(cache_path),
+        None if root.join(CACHE_DIR_NAME).is_dir() => {
+            root.join(CACHE_DIR_NAME).join(CACHE_FILE_NAME)
+        }
+        None => return Ok(None),
+    };
+
+    Ok(Some(Cache::load(&cache_path, config)))
+}
+
+fn get_project_root() -> Result<PathBuf> {
+    if let Some(root) = find_config_file().and_then(|path| path.parent().map(Path::to_path_buf)) {
+        return Ok(root);
+    }
+
+    std::env::current_dir().context("Unable to read the current directory.")
+}
+
+fn find_config_file() -> Option<PathBuf> {
+    let current_dir = std::env::current_dir().ok()?;
+
+    current_dir
+        .ancestors()
+        .map(|dir| dir.join(CONFIG_FILE_NAME))
+        .find(|path| path.is_file())
+}
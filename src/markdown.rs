@@ -0,0 +1,174 @@
+use crate::config::Config;
+use crate::formatter::format_code_with_config;
+use std::fmt;
+
+pub const MARKDOWN_EXTENSIONS: [&str; 2] = ["md", "markdown"];
+
+const FENCE_CHARS: [char; 2] = ['`', '~'];
+const FENCE_MIN_LENGTH: usize = 3;
+const GDSCRIPT_LANGUAGES: [&str; 2] = ["gdscript", "gd"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarkdownFormat {
+    pub code: String,
+    pub errors: Vec<CodeBlockError>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeBlockError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for CodeBlockError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.line, self.message)
+    }
+}
+
+struct Fence<'a> {
+    indent: &'a str,
+    marker: char,
+    length: usize,
+    info: &'a str,
+}
+
+pub fn format_markdown(source: &str, config: &Config) -> MarkdownFormat {
+    let lines: Vec<&str> = source.split_inclusive('\n').collect();
+    let mut code = String::new();
+    let mut errors = Vec::new();
+    let mut index = 0;
+
+    while index < lines.len() {
+        let Some(fence) = parse_fence(lines[index]) else {
+            code.push_str(lines[index]);
+            index += 1;
+            continue;
+        };
+        let Some(closing_index) =
+            (index + 1..lines.len()).find(|&i| is_closing_fence(&fence, lines[i]))
+        else {
+            lines[index..].iter().for_each(|line| code.push_str(line));
+            break;
+        };
+
+        let block = &lines[index + 1..closing_index];
+        code.push_str(lines[index]);
+        match format_block(&fence, block, config) {
+            Some(Ok(text)) => code.push_str(&text),
+            Some(Err(message)) => {
+                errors.push(CodeBlockError {
+                    line: index + 1,
+                    message,
+                });
+                block.iter().for_each(|line| code.push_str(line));
+            }
+            None => block.iter().for_each(|line| code.push_str(line)),
+        }
+        code.push_str(lines[closing_index]);
+        index = closing_index + 1;
+    }
+
+    MarkdownFormat { code, errors }
+}
+
+fn parse_fence(line: &str) -> Option<Fence<'_>> {
+    let text = line.trim_start_matches(' ');
+    let indent = &line[..line.len() - text.len()];
+    let marker = text.chars().next().filter(|c| FENCE_CHARS.contains(c))?;
+    let length = text.len() - text.trim_start_matches(marker).len();
+    let info = text[length..].trim();
+    if length < FENCE_MIN_LENGTH || (marker == '`' && info.contains('`')) {
+        return None;
+    }
+
+    Some(Fence {
+        indent,
+        marker,
+        length,
+        info,
+    })
+}
+
+fn is_closing_fence(fence: &Fence, line: &str) -> bool {
+    parse_fence(line).is_some_and(|closing| {
+        closing.marker == fence.marker && closing.length >= fence.length && closing.info.is_empty()
+    })
+}
+
+fn format_block(fence: &Fence, block: &[&str], config: &Config) -> Option<Result<String, String>> {
+    let language = fence.info.split_whitespace().next().unwrap_or_default();
+    if !GDSCRIPT_LANGUAGES.contains(&language.to_lowercase().as_str()) || block.is_empty() {
+        return None;
+    }
+
+    let source: String = block
+        .iter()
+        .map(|line| {
+            line.strip_prefix(fence.indent)
+                .unwrap_or(line.trim_start_matches(' '))
+        })
+        .collect();
+    let formatted = match format_code_with_config(&source, config) {
+        Ok(formatted) => formatted,
+        Err(error) => return Some(Err(error.to_string())),
+    };
+
+    let mut output = String::new();
+    for line in formatted.split_inclusive('\n') {
+        if line != "\n" {
+            output.push_str(fence.indent);
+        }
+        output.push_str(line);
+    }
+
+    Some(Ok(output))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case(
+        "# Title\n\nSome  prose.\n\n```gdscript\nvar  a = 1\n```\n",
+        "# Title\n\nSome  prose.\n\n```gdscript\nvar a = 1\n```\n"
+    )]
+    #[case("```gd\nvar  a = 1\n```", "```gd\nvar a = 1\n```")]
+    #[case(
+        "~~~~ GDScript title\nvar  a = 1\n~~~~\n",
+        "~~~~ GDScript title\nvar a = 1\n~~~~\n"
+    )]
+    #[case(
+        "- Item:\n\n  ```gdscript\n  func f():\n      var  a = 1\n\n  ```\n",
+        "- Item:\n\n  ```gdscript\n  func f():\n  \tvar a = 1\n  ```\n"
+    )]
+    #[case("```python\nvar  a = 1\n```\n", "```python\nvar  a = 1\n```\n")]
+    #[case(
+        "````markdown\n```gdscript\nvar  a = 1\n```\n````\n",
+        "````markdown\n```gdscript\nvar  a = 1\n```\n````\n"
+    )]
+    #[case("```gdscript\nvar  a = 1\n", "```gdscript\nvar  a = 1\n")]
+    #[case("```gdscript\n```\n", "```gdscript\n```\n")]
+    fn format_gdscript_code_blocks(#[case] source_input: &str, #[case] expected_output: &str) {
+        let formatted = format_markdown(source_input, &Config::default());
+
+        assert_eq!(formatted.code, expected_output);
+        assert!(formatted.errors.is_empty());
+    }
+
+    #[rstest]
+    fn report_code_blocks_with_syntax_errors() {
+        let source = "Text\n\n```gdscript\nvar  a = \n```\n\n```gd\nvar  b = 1\n```\n";
+
+        let formatted = format_markdown(source, &Config::default());
+
+        assert_eq!(
+            formatted.code,
+            "Text\n\n```gdscript\nvar  a = \n```\n\n```gd\nvar b = 1\n```\n"
+        );
+        assert_eq!(formatted.errors.len(), 1);
+        assert_eq!(formatted.errors[0].line, 3);
+    }
+}
@@ -0,0 +1,473 @@
+use crate::error::Error;
+use crate::formatter::parse;
+use crate::node::{get_column, get_node_text};
+use std::fmt;
+use std::ops::Range;
+use tree_sitter::Node;
+
+const RANGE_EXPORT_TYPES: [&str; 2] = ["int", "float"];
+const ENUM_EXPORT_TYPES: [&str; 2] = ["int", "String"];
+const RANGE_EXPORT_OPTIONS: [&str; 6] = [
+    "or_greater",
+    "or_less",
+    "exp",
+    "hide_slider",
+    "radians_as_degrees",
+    "degrees",
+];
+const STRING_EXPORT_HINTS: [(&str, &str); 3] = [
+    ("FILE", "@export_file"),
+    ("DIR", "@export_dir"),
+    ("MULTILINE", "@export_multiline"),
+];
+const AWAIT_OPERAND_KINDS: [&str; 6] = [
+    "identifier",
+    "attribute",
+    "call",
+    "get_node",
+    "subscript",
+    "parenthesized_expression",
+];
+const AWAIT_PARENTHESIZED_PARENTS: [&str; 4] = [
+    "attribute",
+    "subscript",
+    "binary_operator",
+    "unary_operator",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Migration {
+    pub code: String,
+    pub issues: Vec<MigrationIssue>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationIssue {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for MigrationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+struct Edit {
+    range: Range<usize>,
+    text: String,
+}
+
+struct Migrator<'a> {
+    source: &'a str,
+    edits: Vec<Edit>,
+    issues: Vec<MigrationIssue>,
+}
+
+pub fn migrate_code(source: &str) -> Result<Migration, Error> {
+    let tree = parse(source)?;
+    let mut migrator = Migrator {
+        source,
+        edits: Vec::new(),
+        issues: Vec::new(),
+    };
+
+    migrator.visit(tree.root_node());
+
+    Ok(Migration {
+        code: apply_edits(source, migrator.edits),
+        issues: migrator.issues,
+    })
+}
+
+impl Migrator<'_> {
+    fn visit(&mut self, node: Node) {
+        match node.kind() {
+            "tool_statement" => self.replace(node.byte_range(), "@tool"),
+            "export_variable_statement" => self.migrate_export(node),
+            "onready_variable_statement" => {
+                if let Some(keyword) = node.child(0) {
+                    self.replace(keyword.byte_range(), "@onready");
+                }
+            }
+            "setget" if node.is_named() => self.migrate_setget(node),
+            "call" if self.is_yield(node) && self.migrate_yield(node) => return,
+            _ => {}
+        }
+
+        for child in node.children(&mut node.walk()) {
+            self.visit(child);
+        }
+    }
+
+    fn migrate_export(&mut self, node: Node) {
+        let Some(keyword) = node.child(0) else {
+            return;
+        };
+        let Some(arguments) = keyword.next_sibling().filter(|n| n.kind() == "arguments") else {
+            self.replace(keyword.byte_range(), "@export");
+            return;
+        };
+
+        let values: Vec<Node> = arguments.named_children(&mut arguments.walk()).collect();
+        let Some(annotation) = self.get_export_annotation(&values) else {
+            let text = get_node_text(arguments, self.source);
+            self.report(node, format!("cannot migrate export hint `export{}`", text));
+            return;
+        };
+
+        self.replace(keyword.start_byte()..arguments.end_byte(), &annotation);
+
+        let name = node.child_by_field_name("name");
+        let has_type = name
+            .and_then(|n| n.next_sibling())
+            .is_some_and(|n| matches!(n.kind(), ":" | "inferred_type"));
+        if let Some(name) = name.filter(|_| !has_type) {
+            let type_hint = format!(": {}", get_node_text(values[0], self.source));
+            self.replace(name.end_byte()..name.end_byte(), &type_hint);
+        }
+    }
+
+    fn get_export_annotation(&self, values: &[Node]) -> Option<String> {
+        let texts: Vec<&str> = values
+            .iter()
+            .map(|n| get_node_text(*n, self.source))
+            .collect();
+        let (type_name, hints) = texts.split_first()?;
+        let hint_nodes = &values[1..];
+
+        match (*type_name, hints) {
+            (_, []) => Some("@export".to_string()),
+            ("int", ["FLAGS", names @ ..])
+                if !names.is_empty() && hint_nodes[1..].iter().all(is_string) =>
+            {
+                Some(format!("@export_flags({})", names.join(", ")))
+            }
+            (type_name, _)
+                if ENUM_EXPORT_TYPES.contains(&type_name) && hint_nodes.iter().all(is_string) =>
+            {
+                Some(format!("@export_enum({})", hints.join(", ")))
+            }
+            (type_name, _) if RANGE_EXPORT_TYPES.contains(&type_name) => {
+                get_range_annotation(hint_nodes, hints)
+            }
+            ("String", [hint, rest @ ..]) => STRING_EXPORT_HINTS
+                .iter()
+                .find(|(name, _)| name == hint)
+                .map(|(_, annotation)| match rest {
+                    [] => annotation.to_string(),
+                    _ => format!("{}({})", annotation, rest.join(", ")),
+                }),
+            _ => None,
+        }
+    }
+
+    fn migrate_setget(&mut self, node: Node) {
+        let mut accessors = Vec::new();
+        for child in node.children(&mut node.walk()) {
+            match child.kind() {
+                "setter" => accessors.push(format!("set = {}", get_node_text(child, self.source))),
+                "getter" => accessors.push(format!("get = {}", get_node_text(child, self.source))),
+                _ => {}
+            }
+        }
+
+        let start_byte = node
+            .prev_sibling()
+            .map_or(node.start_byte(), |n| n.end_byte());
+        let text = format!(
+            ":\n{}\t{}",
+            self.get_line_indent(node),
+            accessors.join(", ")
+        );
+        self.replace(start_byte..node.end_byte(), &text);
+    }
+
+    fn is_yield(&self, node: Node) -> bool {
+        node.child(0)
+            .is_some_and(|n| get_node_text(n, self.source) == "yield")
+    }
+
+    fn migrate_yield(&mut self, node: Node) -> bool {
+        let arguments: Vec<Node> = node
+            .child_by_field_name("arguments")
+            .or_else(|| node.child(1))
+            .map(|n| n.named_children(&mut n.walk()).collect())
+            .unwrap_or_default();
+
+        let [object, signal] = arguments.as_slice() else {
+            self.report(
+                node,
+                "cannot migrate `yield` without an object and a signal; rewrite it with `await`"
+                    .to_string(),
+            );
+            return false;
+        };
+        let Some(signal) = self.get_signal_name(*signal) else {
+            self.report(
+                node,
+                "cannot migrate `yield` with a signal name that is not a string literal"
+                    .to_string(),
+            );
+            return false;
+        };
+
+        let object_text = self.migrate_node_text(*object);
+        let object_text = if AWAIT_OPERAND_KINDS.contains(&object.kind()) && !self.is_yield(*object)
+        {
+            object_text
+        } else {
+            format!("({})", object_text)
+        };
+        let mut text = format!("await {}.{}", object_text, signal);
+        if node
+            .parent()
+            .is_some_and(|p| AWAIT_PARENTHESIZED_PARENTS.contains(&p.kind()))
+        {
+            text = format!("({})", text);
+        }
+
+        self.replace(node.byte_range(), &text);
+        true
+    }
+
+    fn get_signal_name(&self, node: Node) -> Option<String> {
+        if node.kind() != "string" {
+            return None;
+        }
+        let text = get_node_text(node, self.source);
+        let name = text.get(1..text.len().saturating_sub(1))?;
+        let is_identifier = name.chars().next().is_some_and(|c| !c.is_ascii_digit())
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+
+        is_identifier.then(|| name.to_string())
+    }
+
+    fn get_line_indent(&self, node: Node) -> &str {
+        let line_start = self.source[..node.start_byte()]
+            .rfind('\n')
+            .map_or(0, |i| i + 1);
+        let line = &self.source[line_start..];
+
+        &line[..line.len() - line.trim_start_matches([' ', '\t']).len()]
+    }
+
+    /// Text of a node replaced as a whole, with the migrations inside it applied.
+    fn migrate_node_text(&mut self, node: Node) -> String {
+        let mut migrator = Migrator {
+            source: self.source,
+            edits: Vec::new(),
+            issues: Vec::new(),
+        };
+        migrator.visit(node);
+        self.issues.append(&mut migrator.issues);
+
+        let prefix = &self.source[..node.end_byte()];
+        apply_edits(prefix, migrator.edits)[node.start_byte()..].to_string()
+    }
+
+    fn replace(&mut self, range: Range<usize>, text: &str) {
+        self.edits.push(Edit {
+            range,
+            text: text.to_string(),
+        });
+    }
+
+    fn report(&mut self, node: Node, message: String) {
+        self.issues.push(MigrationIssue {
+            line: node.start_position().row + 1,
+            column: get_column(node, self.source),
+            message,
+        });
+    }
+}
+
+/// Numeric `export` hints are the range bounds and step, optionally followed by
+/// range options like `"or_greater"`. A single number is the maximum.
+fn get_range_annotation(nodes: &[Node], texts: &[&str]) -> Option<String> {
+    let count = nodes.iter().take_while(|n| is_number(n)).count();
+    let (bounds, options) = texts.split_at(count);
+    let is_option = |text: &&str| RANGE_EXPORT_OPTIONS.contains(&text.trim_matches(['"', '\'']));
+    if !nodes[count..].iter().all(is_string) || !options.iter().all(is_option) {
+        return None;
+    }
+
+    let bounds = match bounds {
+        [max] => vec!["0", max],
+        [_, _] | [_, _, _] => bounds.to_vec(),
+        _ => return None,
+    };
+
+    Some(format!(
+        "@export_range({})",
+        [bounds, options.to_vec()].concat().join(", ")
+    ))
+}
+
+fn is_number(node: &Node) -> bool {
+    match node.kind() {
+        "integer" | "float" => true,
+        "unary_operator" => {
+            node.child(0).is_some_and(|n| matches!(n.kind(), "-" | "+"))
+                && node.named_child(0).is_some_and(|n| is_number(&n))
+        }
+        _ => false,
+    }
+}
+
+fn is_string(node: &Node) -> bool {
+    node.kind() == "string"
+}
+
+fn apply_edits(source: &str, mut edits: Vec<Edit>) -> String {
+    edits.sort_by_key(|edit| (edit.range.start, edit.range.end));
+
+    let mut output = String::new();
+    let mut position = 0;
+
+    for edit in edits {
+        output.push_str(&source[position..edit.range.start]);
+        output.push_str(&edit.text);
+        position = edit.range.end;
+    }
+    output.push_str(&source[position..]);
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case("tool\nextends Node\n", "@tool\nextends Node\n")]
+    #[case("export var a = 1\n", "@export var a = 1\n")]
+    #[case("onready var a = $A\n", "@onready var a = $A\n")]
+    #[case("export(int) var a\n", "@export var a: int\n")]
+    #[case("export(Texture) var a: Texture\n", "@export var a: Texture\n")]
+    #[case(
+        "export(float, -1.5, 2) var a := 1.0\n",
+        "@export_range(-1.5, 2) var a := 1.0\n"
+    )]
+    #[case(
+        "export(int, 0, 10) var a = 1\n",
+        "@export_range(0, 10) var a: int = 1\n"
+    )]
+    #[case(
+        "export(String, FILE, \"*.png\") var a\n",
+        "@export_file(\"*.png\") var a: String\n"
+    )]
+    #[case(
+        "export(String, MULTILINE) var a\n",
+        "@export_multiline var a: String\n"
+    )]
+    #[case("export(int, 10) var a\n", "@export_range(0, 10) var a: int\n")]
+    #[case(
+        "export(float, 0, 1, 0.1) var a\n",
+        "@export_range(0, 1, 0.1) var a: float\n"
+    )]
+    #[case(
+        "export(int, 0, 10, \"or_greater\") var a\n",
+        "@export_range(0, 10, \"or_greater\") var a: int\n"
+    )]
+    #[case(
+        "export(int, \"A\", \"B\") var a\n",
+        "@export_enum(\"A\", \"B\") var a: int\n"
+    )]
+    #[case(
+        "export(String, \"a\", \"b\") var a\n",
+        "@export_enum(\"a\", \"b\") var a: String\n"
+    )]
+    #[case(
+        "export(int, FLAGS, \"X\", \"Y\") var a\n",
+        "@export_flags(\"X\", \"Y\") var a: int\n"
+    )]
+    fn migrate_annotations(#[case] source_input: &str, #[case] expected_output: &str) {
+        let migration = migrate_code(source_input).unwrap();
+
+        assert_eq!(migration.code, expected_output);
+        assert!(migration.issues.is_empty());
+    }
+
+    #[rstest]
+    #[case("var a = 1 setget set_a\n", "var a = 1:\n\tset = set_a\n")]
+    #[case("var a setget , get_a\n", "var a:\n\tget = get_a\n")]
+    #[case(
+        "var a: int setget set_a, get_a # c\n",
+        "var a: int:\n\tset = set_a, get = get_a # c\n"
+    )]
+    #[case(
+        "export var a = 1 setget set_a, get_a\n",
+        "@export var a = 1:\n\tset = set_a, get = get_a\n"
+    )]
+    #[case(
+        "class A:\n\tvar a = 1 setget set_a\n",
+        "class A:\n\tvar a = 1:\n\t\tset = set_a\n"
+    )]
+    fn migrate_setget(#[case] source_input: &str, #[case] expected_output: &str) {
+        let migration = migrate_code(source_input).unwrap();
+
+        assert_eq!(migration.code, expected_output);
+        assert!(migration.issues.is_empty());
+    }
+
+    #[rstest]
+    #[case(
+        "func f():\n\tyield(get_tree(), \"idle_frame\")\n",
+        "func f():\n\tawait get_tree().idle_frame\n"
+    )]
+    #[case(
+        "func f():\n\tvar x = yield(self, \"done\")\n",
+        "func f():\n\tvar x = await self.done\n"
+    )]
+    #[case(
+        "func f():\n\tyield(get_tree().create_timer(1.0), 'timeout')\n",
+        "func f():\n\tawait get_tree().create_timer(1.0).timeout\n"
+    )]
+    #[case("func f():\n\tyield(a, \"b\").c()\n", "func f():\n\t(await a.b).c()\n")]
+    #[case(
+        "func f():\n\tyield(a if b else c, \"d\")\n",
+        "func f():\n\tawait (a if b else c).d\n"
+    )]
+    #[case(
+        "func f():\n\tyield(yield(a, \"b\"), \"c\")\n",
+        "func f():\n\tawait (await a.b).c\n"
+    )]
+    #[case(
+        "func f():\n\tyield(yield(a, \"b\").d, \"c\")\n",
+        "func f():\n\tawait (await a.b).d.c\n"
+    )]
+    fn migrate_yield(#[case] source_input: &str, #[case] expected_output: &str) {
+        let migration = migrate_code(source_input).unwrap();
+
+        assert_eq!(migration.code, expected_output);
+        assert!(migration.issues.is_empty());
+    }
+
+    #[rstest]
+    #[case("export(Array, int) var a\n", 1, 1)]
+    #[case("export(float, EXP, 100, 1000) var a\n", 1, 1)]
+    #[case("export(int, LAYERS_2D_PHYSICS) var a\n", 1, 1)]
+    #[case("export(int, 0, 10, \"suffix\") var a\n", 1, 1)]
+    #[case("export(int, 0, 1, 2, 3) var a\n", 1, 1)]
+    #[case("export(float, MAX_SPEED) var a\n", 1, 1)]
+    #[case("export(int, FLAGS) var a\n", 1, 1)]
+    #[case("func f():\n\tyield()\n", 2, 2)]
+    #[case("func f():\n\tvar s = \"done\"\n\tyield(self, s)\n", 3, 2)]
+    fn report_unsafe_migrations(
+        #[case] source_input: &str,
+        #[case] line: usize,
+        #[case] column: usize,
+    ) {
+        let migration = migrate_code(source_input).unwrap();
+
+        assert_eq!(migration.code, source_input);
+        assert_eq!(migration.issues.len(), 1);
+        assert_eq!(
+            (migration.issues[0].line, migration.issues[0].column),
+            (line, column)
+        );
+    }
+}
@@ -6,22 +6,72 @@ const KINDS_WITH_TWO_LINES_BETWEEN: [&str; 3] = [
     "constructor_definition",
 ];
 
+/// Methods Godot calls on its own, which keep their `_` prefix.
+pub const VIRTUAL_METHODS: [&str; 25] = [
+    "_init",
+    "_static_init",
+    "_enter_tree",
+    "_ready",
+    "_process",
+    "_physics_process",
+    "_exit_tree",
+    "_input",
+    "_unhandled_input",
+    "_unhandled_key_input",
+    "_shortcut_input",
+    "_gui_input",
+    "_draw",
+    "_notification",
+    "_integrate_forces",
+    "_get_configuration_warnings",
+    "_to_string",
+    "_get",
+    "_set",
+    "_get_property_list",
+    "_property_can_revert",
+    "_property_get_revert",
+    "_validate_property",
+    "_can_drop_data",
+    "_drop_data",
+];
+
 pub fn get_node_text<'a>(node: Node<'a>, source: &'a str) -> &'a str {
     &source[node.byte_range()]
 }
 
+/// One-based column of the node, counted in characters rather than bytes.
+pub fn get_column(node: Node, source: &str) -> usize {
+    let start_byte = node.start_byte();
+    let line_start = source[..start_byte]
+        .rfind('\n')
+        .map_or(0, |index| index + 1);
+
+    source[line_start..start_byte].chars().count() + 1
+}
+
 pub fn get_gap_lines(node: Node, source: &str) -> String {
     let lines = match (
         KINDS_WITH_TWO_LINES_BETWEEN.contains(&node.kind()),
         node.parent().map(|n| n.kind()),
-        node.prev_sibling().map(|n| n.kind()),
+        node.prev_sibling().map(is_leading_comment),
     ) {
-        (true, Some("source"), Some(prev_kind)) if prev_kind != "comment" => "\n\n",
+        (true, Some("source"), Some(false)) => "\n\n",
         _ => &get_normalized_gap_lines(node, source),
     };
     lines.to_string()
 }
 
+pub fn is_trailing_comment(node: Node) -> bool {
+    node.kind() == "comment"
+        && node
+            .prev_sibling()
+            .is_some_and(|prev| prev.end_position().row == node.start_position().row)
+}
+
+fn is_leading_comment(node: Node) -> bool {
+    node.kind() == "comment" && !is_trailing_comment(node)
+}
+
 fn get_normalized_gap_lines(node: Node, source: &str) -> String {
     let previous = node.prev_sibling();
     let gap_start_byte = if let Some(prev_node) = previous {
@@ -0,0 +1,343 @@
+use crate::config::Severity;
+use crate::lint::{Diagnostic, FILE_ERROR_RULE, RULES};
+use serde::{Serialize, Serializer};
+use serde_json::json;
+use std::path::{Component, Path};
+use std::time::Duration;
+
+const TOOL_NAME: &str = "gdfmt";
+const TOOL_URI: &str = "https://github.com/emersonmx/gdfmt";
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+const SARIF_ROOT_ID: &str = "%SRCROOT%";
+const CHECKSTYLE_VERSION: &str = "4.3";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileStatus {
+    Unchanged,
+    Formatted,
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileReport {
+    pub path: String,
+    pub status: FileStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub diagnostics: Vec<Diagnostic>,
+    #[serde(rename = "duration_ms", serialize_with = "serialize_milliseconds")]
+    pub duration: Duration,
+}
+
+#[derive(Serialize)]
+struct Report<'a> {
+    files: &'a [FileReport],
+}
+
+pub fn to_json(reports: &[FileReport]) -> String {
+    serde_json::to_string_pretty(&Report { files: reports })
+        .expect("file reports are always serializable")
+}
+
+pub fn to_sarif(reports: &[FileReport], root: &Path) -> String {
+    let rules: Vec<_> = RULES
+        .iter()
+        .map(|(id, severity)| {
+            json!({
+                "id": id,
+                "defaultConfiguration": { "level": get_sarif_level(*severity) },
+            })
+        })
+        .collect();
+    let results: Vec<_> = reports
+        .iter()
+        .flat_map(|report| {
+            let uri = encode_uri(&get_relative_path(&report.path, root));
+            get_findings(report).into_iter().map(move |finding| {
+                json!({
+                    "ruleId": finding.rule,
+                    "level": get_sarif_level(finding.severity),
+                    "message": { "text": finding.message },
+                    "locations": [{
+                        "physicalLocation": {
+                            "artifactLocation": { "uri": uri, "uriBaseId": SARIF_ROOT_ID },
+                            "region": {
+                                "startLine": finding.line,
+                                "startColumn": finding.column,
+                            },
+                        },
+                    }],
+                })
+            })
+        })
+        .collect();
+
+    let sarif = json!({
+        "$schema": SARIF_SCHEMA,
+        "version": SARIF_VERSION,
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": TOOL_NAME,
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": TOOL_URI,
+                    "rules": rules,
+                },
+            },
+            "originalUriBaseIds": {
+                SARIF_ROOT_ID: { "uri": format!("file://{}/", encode_uri(&root.to_string_lossy())) },
+            },
+            "results": results,
+        }],
+    });
+
+    serde_json::to_string_pretty(&sarif).expect("SARIF reports are always serializable")
+}
+
+pub fn to_checkstyle(reports: &[FileReport], root: &Path) -> String {
+    let mut output = String::new();
+
+    output.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    output.push_str(&format!(
+        "<checkstyle version=\"{}\">\n",
+        CHECKSTYLE_VERSION
+    ));
+    for report in reports {
+        let findings = get_findings(report);
+        if findings.is_empty() {
+            continue;
+        }
+        output.push_str(&format!(
+            "  <file name=\"{}\">\n",
+            escape_xml(&get_relative_path(&report.path, root))
+        ));
+        for finding in findings {
+            output.push_str(&format!(
+                "    <error line=\"{}\" column=\"{}\" severity=\"{}\" message=\"{}\" source=\"{}.{}\"/>\n",
+                finding.line,
+                finding.column,
+                finding.severity,
+                escape_xml(&finding.message),
+                TOOL_NAME,
+                finding.rule,
+            ));
+        }
+        output.push_str("  </file>\n");
+    }
+    output.push_str("</checkstyle>\n");
+
+    output
+}
+
+fn get_findings(report: &FileReport) -> Vec<Diagnostic> {
+    match &report.error {
+        Some(error) if report.diagnostics.is_empty() => {
+            vec![Diagnostic::at(FILE_ERROR_RULE, 1, 1, error.clone())]
+        }
+        _ => report.diagnostics.clone(),
+    }
+}
+
+fn get_sarif_level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Off => "none",
+        Severity::Warning => "warning",
+        Severity::Error => "error",
+    }
+}
+
+fn get_relative_path(file_path: &str, root: &Path) -> String {
+    let path = std::path::absolute(file_path).unwrap_or_else(|_| Path::new(file_path).into());
+    let Ok(relative_path) = path.strip_prefix(root) else {
+        return file_path.to_string();
+    };
+    let components: Vec<_> = relative_path
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => Some(name.to_string_lossy()),
+            _ => None,
+        })
+        .collect();
+
+    components.join("/")
+}
+
+fn encode_uri(text: &str) -> String {
+    let mut output = String::new();
+
+    for byte in text.bytes() {
+        match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                output.push(byte as char)
+            }
+            _ => output.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+
+    output
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn serialize_milliseconds<S: Serializer>(
+    duration: &Duration,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(duration.as_secs_f64() * 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Config, lint_code};
+    use rstest::*;
+
+    fn get_reports() -> Vec<FileReport> {
+        vec![
+            FileReport {
+                path: "/project/scripts/my player.gd".to_string(),
+                status: FileStatus::Unchanged,
+                error: None,
+                diagnostics: lint_code("var myVar", &Config::default()).unwrap(),
+                duration: Duration::ZERO,
+            },
+            FileReport {
+                path: "/project/broken.tscn".to_string(),
+                status: FileStatus::Error,
+                error: Some("unable to parse: <\"x\">".to_string()),
+                diagnostics: Vec::new(),
+                duration: Duration::ZERO,
+            },
+            FileReport {
+                path: "/project/clean.gd".to_string(),
+                status: FileStatus::Unchanged,
+                error: None,
+                diagnostics: Vec::new(),
+                duration: Duration::ZERO,
+            },
+        ]
+    }
+
+    #[rstest]
+    fn write_reports_as_json() {
+        let reports = [
+            FileReport {
+                path: "player.gd".to_string(),
+                status: FileStatus::Formatted,
+                error: None,
+                diagnostics: lint_code("var myVar", &Config::default()).unwrap(),
+                duration: Duration::from_micros(1500),
+            },
+            FileReport {
+                path: "broken.gd".to_string(),
+                status: FileStatus::Error,
+                error: Some("unable to parse".to_string()),
+                diagnostics: Vec::new(),
+                duration: Duration::ZERO,
+            },
+        ];
+
+        let json: serde_json::Value = serde_json::from_str(&to_json(&reports)).unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "files": [
+                    {
+                        "path": "player.gd",
+                        "status": "formatted",
+                        "diagnostics": [
+                            {
+                                "rule": "variable-name",
+                                "severity": "warning",
+                                "line": 1,
+                                "column": 5,
+                                "message": "`myVar` should be written in snake_case",
+                            },
+                        ],
+                        "duration_ms": 1.5,
+                    },
+                    {
+                        "path": "broken.gd",
+                        "status": "error",
+                        "error": "unable to parse",
+                        "diagnostics": [],
+                        "duration_ms": 0.0,
+                    },
+                ],
+            })
+        );
+    }
+
+    #[rstest]
+    fn write_reports_as_sarif() {
+        let sarif = to_sarif(&get_reports(), Path::new("/project"));
+
+        let sarif: serde_json::Value = serde_json::from_str(&sarif).unwrap();
+        let run = &sarif["runs"][0];
+
+        assert_eq!(sarif["version"], "2.1.0");
+        assert_eq!(
+            run["originalUriBaseIds"]["%SRCROOT%"]["uri"],
+            "file:///project/"
+        );
+        assert_eq!(
+            run["results"],
+            serde_json::json!([
+                {
+                    "ruleId": "variable-name",
+                    "level": "warning",
+                    "message": { "text": "`myVar` should be written in snake_case" },
+                    "locations": [{
+                        "physicalLocation": {
+                            "artifactLocation": {
+                                "uri": "scripts/my%20player.gd",
+                                "uriBaseId": "%SRCROOT%",
+                            },
+                            "region": { "startLine": 1, "startColumn": 5 },
+                        },
+                    }],
+                },
+                {
+                    "ruleId": "file-error",
+                    "level": "error",
+                    "message": { "text": "unable to parse: <\"x\">" },
+                    "locations": [{
+                        "physicalLocation": {
+                            "artifactLocation": { "uri": "broken.tscn", "uriBaseId": "%SRCROOT%" },
+                            "region": { "startLine": 1, "startColumn": 1 },
+                        },
+                    }],
+                },
+            ])
+        );
+    }
+
+    #[rstest]
+    fn write_reports_as_checkstyle() {
+        let checkstyle = to_checkstyle(&get_reports(), Path::new("/project"));
+
+        assert_eq!(
+            checkstyle,
+            concat!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+                "<checkstyle version=\"4.3\">\n",
+                "  <file name=\"scripts/my player.gd\">\n",
+                "    <error line=\"1\" column=\"5\" severity=\"warning\" message=\"`myVar` should be written in snake_case\" source=\"gdfmt.variable-name\"/>\n",
+                "  </file>\n",
+                "  <file name=\"broken.tscn\">\n",
+                "    <error line=\"1\" column=\"1\" severity=\"error\" message=\"unable to parse: &lt;&quot;x&quot;&gt;\" source=\"gdfmt.file-error\"/>\n",
+                "  </file>\n",
+                "</checkstyle>\n",
+            )
+        );
+    }
+}
@@ -0,0 +1,233 @@
+use crate::config::Config;
+use crate::formatter::format_code_with_config;
+use crate::markdown::CodeBlockError;
+use std::ops::Range;
+
+pub const RESOURCE_EXTENSIONS: [&str; 2] = ["tscn", "tres"];
+
+const SCRIPT_TYPE: &str = "type=\"GDScript\"";
+const SCRIPT_SOURCE_KEY: &str = "script/source";
+const SUB_RESOURCE_HEADER: &str = "[sub_resource ";
+const GD_RESOURCE_HEADER: &str = "[gd_resource ";
+const RESOURCE_HEADER: &str = "[resource]";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResourceFormat {
+    pub code: String,
+    pub errors: Vec<CodeBlockError>,
+}
+
+struct ScriptSource<'a> {
+    header: &'a str,
+    header_line: usize,
+    range: Range<usize>,
+}
+
+/// Formats the scripts embedded in a scene or resource. A script that can't be
+/// formatted is left unchanged and reported at the line of its section header.
+pub fn format_resource(source: &str, config: &Config) -> ResourceFormat {
+    let mut code = String::new();
+    let mut errors = Vec::new();
+    let mut position = 0;
+
+    for script_source in find_script_sources(source) {
+        let range = script_source.range;
+        let script = unescape(&source[range.clone()]);
+        let formatted = match format_code_with_config(&script, config) {
+            Ok(formatted) => formatted,
+            Err(error) => {
+                errors.push(CodeBlockError {
+                    line: script_source.header_line,
+                    message: format!("{}: {}", script_source.header, error),
+                });
+                continue;
+            }
+        };
+        if formatted == script {
+            continue;
+        }
+
+        code.push_str(&source[position..range.start]);
+        code.push_str(&escape(&formatted));
+        position = range.end;
+    }
+    code.push_str(&source[position..]);
+
+    ResourceFormat { code, errors }
+}
+
+fn find_script_sources(source: &str) -> Vec<ScriptSource<'_>> {
+    let mut script_sources = Vec::new();
+    let mut is_script_resource = false;
+    let mut section = None;
+    let mut position = 0;
+    let mut line_number = 1;
+
+    while position < source.len() {
+        let line = &source[position..];
+        let line_end = position + find_line_end(line);
+
+        if line.starts_with('[') {
+            let header = &source[position..line_end];
+            if header.starts_with(GD_RESOURCE_HEADER) {
+                is_script_resource = header.contains(SCRIPT_TYPE);
+            }
+            let is_script_section = (header.starts_with(SUB_RESOURCE_HEADER)
+                && header.contains(SCRIPT_TYPE))
+                || (header.starts_with(RESOURCE_HEADER) && is_script_resource);
+            section = is_script_section.then_some((header, line_number));
+        } else if let Some((header, header_line)) = section
+            && let Some(value) = line
+                .strip_prefix(SCRIPT_SOURCE_KEY)
+                .map(|rest| rest.trim_start_matches([' ', '=']))
+                .filter(|value| value.starts_with('"'))
+        {
+            let start = position + (line.len() - value.len()) + 1;
+            let end = start + find_string_end(&source[start..]);
+            script_sources.push(ScriptSource {
+                header,
+                header_line,
+                range: start..end,
+            });
+        }
+
+        line_number += source[position..line_end].matches('\n').count() + 1;
+        position = line_end + 1;
+    }
+
+    script_sources
+}
+
+fn find_line_end(text: &str) -> usize {
+    let mut in_string = false;
+    let mut chars = text.char_indices();
+
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '\\' if in_string => {
+                chars.next();
+            }
+            '"' => in_string = !in_string,
+            '\n' if !in_string => return index,
+            _ => {}
+        }
+    }
+
+    text.len()
+}
+
+fn find_string_end(text: &str) -> usize {
+    let mut chars = text.char_indices();
+
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '"' => return index,
+            _ => {}
+        }
+    }
+
+    text.len()
+}
+
+fn unescape(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            output.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => output.push('\n'),
+            Some('t') => output.push('\t'),
+            Some('r') => output.push('\r'),
+            Some('b') => output.push('\u{8}'),
+            Some('f') => output.push('\u{c}'),
+            Some('u') => {
+                let digits: String = chars.by_ref().take(4).collect();
+                let unicode = u32::from_str_radix(&digits, 16)
+                    .ok()
+                    .and_then(char::from_u32);
+                match unicode {
+                    Some(unicode) => output.push(unicode),
+                    None => {
+                        output.push_str("\\u");
+                        output.push_str(&digits);
+                    }
+                }
+            }
+            Some(c) => output.push(c),
+            None => output.push('\\'),
+        }
+    }
+
+    output
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case(
+        "[gd_scene load_steps=2 format=3]\n\n[sub_resource type=\"GDScript\" id=\"GDScript_1\"]\nscript/source = \"extends Node\nfunc _ready():\n    print(\\\"hi\\\")\n\"\n\n[node name=\"Main\" type=\"Node\"]\nscript = SubResource(\"GDScript_1\")\n",
+        "[gd_scene load_steps=2 format=3]\n\n[sub_resource type=\"GDScript\" id=\"GDScript_1\"]\nscript/source = \"extends Node\n\n\nfunc _ready():\n\tprint(\\\"hi\\\")\n\"\n\n[node name=\"Main\" type=\"Node\"]\nscript = SubResource(\"GDScript_1\")\n"
+    )]
+    #[case(
+        "[gd_resource type=\"GDScript\" format=3]\n\n[resource]\nscript/source = \"var  a = 'a\\\\\\\\b'\n\"\n",
+        "[gd_resource type=\"GDScript\" format=3]\n\n[resource]\nscript/source = \"var a = \\\"a\\\\\\\\b\\\"\n\"\n"
+    )]
+    #[case(
+        "[sub_resource type=\"Resource\" id=\"1\"]\nscript/source = \"var  a = 1\"\n",
+        "[sub_resource type=\"Resource\" id=\"1\"]\nscript/source = \"var  a = 1\"\n"
+    )]
+    #[case(
+        "[sub_resource type=\"Resource\" id=\"1\"]\ntext = \"[sub_resource type=\\\"GDScript\\\"]\nscript/source = \\\"var  a = 1\\\"\"\n",
+        "[sub_resource type=\"Resource\" id=\"1\"]\ntext = \"[sub_resource type=\\\"GDScript\\\"]\nscript/source = \\\"var  a = 1\\\"\"\n"
+    )]
+    #[case(
+        "[sub_resource type=\"GDScript\" id=\"1\"]\nscript/source = \"var a = 1\\n\"\n",
+        "[sub_resource type=\"GDScript\" id=\"1\"]\nscript/source = \"var a = 1\\n\"\n"
+    )]
+    fn format_embedded_scripts(#[case] source_input: &str, #[case] expected_output: &str) {
+        let formatted = format_resource(source_input, &Config::default());
+
+        assert_eq!(formatted.code, expected_output);
+        assert!(formatted.errors.is_empty());
+    }
+
+    #[rstest]
+    fn report_scripts_with_syntax_errors() {
+        let source_input = "[gd_scene format=3]\n\n[sub_resource type=\"GDScript\" id=\"1\"]\nscript/source = \"func f(:\n\"\n\n[sub_resource type=\"GDScript\" id=\"2\"]\nscript/source = \"var  a = 1\n\"\n";
+
+        let formatted = format_resource(source_input, &Config::default());
+
+        assert_eq!(
+            formatted.code,
+            source_input.replace("var  a = 1", "var a = 1")
+        );
+        assert_eq!(formatted.errors.len(), 1);
+        assert_eq!(formatted.errors[0].line, 3);
+        assert!(
+            formatted.errors[0]
+                .message
+                .starts_with("[sub_resource type=\"GDScript\" id=\"1\"]: ")
+        );
+    }
+
+    #[rstest]
+    #[case("a\\nb\\t\\\"c\\\"\\\\", "a\nb\t\"c\"\\")]
+    #[case("\\u00e9", "é")]
+    fn unescape_resource_strings(#[case] text: &str, #[case] expected_output: &str) {
+        assert_eq!(unescape(text), expected_output);
+    }
+}
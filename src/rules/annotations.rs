@@ -1,45 +1,89 @@
+use crate::config::Config;
+use crate::node::get_gap_lines;
+use crate::text::indent_by;
 use tree_sitter::Node;
 
-pub fn apply(node: Node, source: &str, indent_level: usize) -> String {
+pub fn write(node: Node, source: &str, config: &Config, indent_level: usize, output: &mut String) {
     match node.kind() {
-        "annotations" => apply_annotations_rules(node, source, indent_level),
-        "annotation" => apply_annotation_rules(node, source, indent_level),
-        _ => super::apply(node, source, indent_level),
+        "annotations" => write_annotations_rules(node, source, config, indent_level, output),
+        "annotation" if super::is_statement(node) => {
+            write_statement_annotation_rules(node, source, config, indent_level, output)
+        }
+        "annotation" => write_annotation_rules(node, source, config, indent_level, output),
+        "arguments" => write_arguments_rules(node, source, config, indent_level, output),
+        _ => super::write(node, source, config, indent_level, output),
     }
 }
 
-fn apply_annotations_rules(node: Node, source: &str, indent_level: usize) -> String {
-    let mut output = String::new();
-
+fn write_annotations_rules(
+    node: Node,
+    source: &str,
+    config: &Config,
+    indent_level: usize,
+    output: &mut String,
+) {
     for child in node.children(&mut node.walk()) {
-        let child_apply_fn = || apply(child, source, indent_level);
-        let (text, space): (&str, &str) = match child.kind() {
-            _ if child.prev_sibling().is_none() => (&child_apply_fn(), ""),
-            "annotation" => (&child_apply_fn(), " "),
-            _ => (&child_apply_fn(), ""),
-        };
-        output.push_str(space);
-        output.push_str(text);
+        match child.kind() {
+            _ if child.prev_sibling().is_none() => {}
+            "annotation" => output.push(' '),
+            _ => {}
+        }
+        write(child, source, config, indent_level, output);
     }
+}
 
-    output
+fn write_annotation_rules(
+    node: Node,
+    source: &str,
+    config: &Config,
+    indent_level: usize,
+    output: &mut String,
+) {
+    for child in node.children(&mut node.walk()) {
+        match child.kind() {
+            "@" => {}
+            "annotation" => output.push(' '),
+            _ => {}
+        }
+        write(child, source, config, indent_level, output);
+    }
 }
 
-fn apply_annotation_rules(node: Node, source: &str, indent_level: usize) -> String {
-    let mut output = String::new();
+fn write_statement_annotation_rules(
+    node: Node,
+    source: &str,
+    config: &Config,
+    indent_level: usize,
+    output: &mut String,
+) {
+    let gap_lines = get_gap_lines(node, source);
 
+    output.push_str(&gap_lines);
+    indent_by(output, indent_level);
+    write_annotation_rules(node, source, config, indent_level, output);
+    output.push('\n');
+}
+
+fn write_arguments_rules(
+    node: Node,
+    source: &str,
+    config: &Config,
+    indent_level: usize,
+    output: &mut String,
+) {
     for child in node.children(&mut node.walk()) {
-        let child_apply_fn = || apply(child, source, indent_level);
-        let (text, space): (&str, &str) = match child.kind() {
-            "@" => (&child_apply_fn(), ""),
-            "annotation" => (&child_apply_fn(), " "),
-            _ => (&child_apply_fn(), ""),
-        };
-        output.push_str(space);
-        output.push_str(text);
+        let prev_kind = child.prev_sibling().map(|ps| ps.kind());
+        match child.kind() {
+            "(" | ")" | "," => {}
+            "comment" => {
+                super::comment::write_inline(child, source, config, indent_level, output);
+                continue;
+            }
+            _ if matches!(prev_kind, Some("(" | "comment")) => {}
+            _ => output.push(' '),
+        }
+        write(child, source, config, indent_level, output);
     }
-
-    output
 }
 
 #[cfg(test)]
@@ -53,9 +97,23 @@ mod tests {
     #[case("  @export  var c = 0", "@export var c = 0\n")]
     #[case("  @onready  @export  var d = 0", "@onready @export var d = 0\n")]
     #[case("  @  onready  @  export  var e = 0", "@onready @export var e = 0\n")]
+    #[case("@export_range( 0,10 ) var f = 0", "@export_range(0, 10) var f = 0\n")]
+    #[case("@tool\nextends Node", "@tool\nextends Node\n")]
+    #[case("@onready\nvar g = 0", "@onready\nvar g = 0\n")]
     fn enforce_spacing_rules(#[case] source_input: &str, #[case] expected_output: &str) {
         let formatted = format_code(source_input).unwrap();
 
         assert_eq!(formatted, expected_output);
     }
+
+    #[rstest]
+    #[case(
+        "@export_range(0, # c\n\t10) var a = 0",
+        "@export_range(0,  # c\n\t10) var a = 0\n"
+    )]
+    fn break_line_after_comments(#[case] source_input: &str, #[case] expected_output: &str) {
+        let formatted = format_code(source_input).unwrap();
+
+        assert_eq!(formatted, expected_output);
+    }
 }
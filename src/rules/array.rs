@@ -1,31 +1,43 @@
+use super::collection;
+use crate::config::Config;
 use tree_sitter::Node;
 
-pub fn apply(node: Node, source: &str, indent_level: usize) -> String {
-    let mut output = String::new();
+pub fn write(node: Node, source: &str, config: &Config, indent_level: usize, output: &mut String) {
+    collection::write(
+        node,
+        source,
+        config,
+        indent_level,
+        output,
+        write_flat_rules,
+        super::write,
+    );
+}
 
+fn write_flat_rules(
+    node: Node,
+    source: &str,
+    config: &Config,
+    indent_level: usize,
+    output: &mut String,
+) {
     for child in node.children(&mut node.walk()) {
         let next_kind = child.next_sibling().map(|ns| ns.kind());
         let prev_kind = child.prev_sibling().map(|ps| ps.kind());
-        let child_apply_fn = || super::apply(child, source, indent_level);
-        let (text, space): (&str, &str) = match child.kind() {
-            "[" => (&child_apply_fn(), ""),
-            "]" if prev_kind == Some("[") => (&child_apply_fn(), ""),
-            "]" => (&child_apply_fn(), ""),
-            "," if next_kind == Some("]") => ("", ""),
-            "," => (&child_apply_fn(), ""),
-            _ if prev_kind == Some("[") => (&child_apply_fn(), ""),
-            _ => (&child_apply_fn(), " "),
-        };
-        output.push_str(space);
-        output.push_str(text);
+        match child.kind() {
+            "[" | "]" => {}
+            "," if next_kind == Some("]") => continue,
+            "," => {}
+            _ if prev_kind == Some("[") => {}
+            _ => output.push(' '),
+        }
+        super::write(child, source, config, indent_level, output);
     }
-
-    output
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::format_code;
+    use crate::{Config, format_code, format_code_with_config};
     use rstest::*;
 
     #[rstest]
@@ -35,10 +47,91 @@ mod tests {
     #[case("var d=[]", "var d = []\n")]
     #[case("var e=[1]", "var e = [1]\n")]
     #[case("var f=[ 1 ]", "var f = [1]\n")]
-    #[case("var g=[ 1, ]", "var g = [1]\n")]
     fn enforce_spacing_rules(#[case] source_input: &str, #[case] expected_output: &str) {
         let formatted = format_code(source_input).unwrap();
 
         assert_eq!(formatted, expected_output);
     }
+
+    #[rstest]
+    #[case("var a = [1,]", "var a = [\n\t1,\n]\n")]
+    #[case("var b = [ 1, 2, ]", "var b = [\n\t1,\n\t2,\n]\n")]
+    #[case("var c = [\n\t1,\n\t2\n]", "var c = [1, 2]\n")]
+    #[case(
+        "var d = [[1, 2,], [3, 4]]",
+        "var d = [\n\t[\n\t\t1,\n\t\t2,\n\t],\n\t[3, 4],\n]\n"
+    )]
+    #[case(
+        "func f():\n\tvar e = [1, 2,]",
+        "func f():\n\tvar e = [\n\t\t1,\n\t\t2,\n\t]\n"
+    )]
+    #[case(
+        "var f = [\n\t1, # one\n\t# two\n\t2,\n]",
+        "var f = [\n\t1,  # one\n\t# two\n\t2,\n]\n"
+    )]
+    #[case("var g = [{\"a\": 1,}]", "var g = [\n\t{\n\t\t\"a\": 1,\n\t},\n]\n")]
+    #[case(
+        "func f(a = [1, 2,]):\n\tpass",
+        "func f(a=[\n\t1,\n\t2,\n]):\n\tpass\n"
+    )]
+    #[case(
+        "func f():\n\tvar a = [1,]:\n\t\tset = g",
+        "func f():\n\tvar a = [\n\t\t1,\n\t]:\n\t\tset = g\n"
+    )]
+    fn split_elements_with_trailing_comma(
+        #[case] source_input: &str,
+        #[case] expected_output: &str,
+    ) {
+        let formatted = format_code(source_input).unwrap();
+
+        assert_eq!(formatted, expected_output);
+    }
+
+    #[rstest]
+    #[case("var a = [1, 2, 3]", "var a = [1, 2, 3]\n")]
+    #[case("var b = [1, 2, 3, 4]", "var b = [\n\t1,\n\t2,\n\t3,\n\t4,\n]\n")]
+    #[case("var c  =  [1, 2, 3]", "var c = [1, 2, 3]\n")]
+    #[case("var d=[1, 2, 3, 4]", "var d = [\n\t1,\n\t2,\n\t3,\n\t4,\n]\n")]
+    fn split_elements_exceeding_line_length(
+        #[case] source_input: &str,
+        #[case] expected_output: &str,
+    ) {
+        let config = Config {
+            line_length: 18,
+            ..Default::default()
+        };
+
+        let formatted = format_code_with_config(source_input, &config).unwrap();
+
+        assert_eq!(formatted, expected_output);
+    }
+
+    #[rstest]
+    #[case("var a=[1, 2, 3, 4, 5, 6, 777]")]
+    #[case("var a  =  [1, 2, 3, 4, 5, 6, 77]")]
+    #[case("func f():\n\tvar a = [1, 2, 3, {\"a\": 1}]")]
+    fn format_idempotently(#[case] source_input: &str) {
+        let config = Config {
+            line_length: 30,
+            ..Default::default()
+        };
+
+        let formatted = format_code_with_config(source_input, &config).unwrap();
+        let reformatted = format_code_with_config(&formatted, &config).unwrap();
+
+        assert_eq!(reformatted, formatted);
+    }
+
+    #[rstest]
+    #[case(30, ",]")]
+    #[case(30, "]")]
+    fn format_deep_nesting_quickly(#[case] depth: usize, #[case] closing: &str) {
+        let source_input = format!("var a = {}1{}", "[".repeat(depth), closing.repeat(depth));
+        let start = std::time::Instant::now();
+
+        let formatted = format_code(&source_input).unwrap();
+
+        assert!(start.elapsed() < std::time::Duration::from_secs(1));
+        assert_eq!(format_code(&formatted).unwrap(), formatted);
+    }
 }
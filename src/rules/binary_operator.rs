@@ -1,21 +1,24 @@
+use crate::config::Config;
 use tree_sitter::Node;
 
-pub fn apply(node: Node, source: &str, indent_level: usize) -> String {
-    let mut output = String::new();
-
+pub fn write(node: Node, source: &str, config: &Config, indent_level: usize, output: &mut String) {
     for child in node.children(&mut node.walk()) {
-        let child_apply_fn = || super::apply(child, source, indent_level);
-        let (text, space): (&str, &str) = match child.kind() {
-            _ if child.prev_sibling().is_none() => (&child_apply_fn(), ""),
-            "&&" => ("and", " "),
-            "||" => ("or", " "),
-            _ => (&child_apply_fn(), " "),
-        };
-        output.push_str(space);
-        output.push_str(text);
+        let prev_kind = child.prev_sibling().map(|ps| ps.kind());
+        match child.kind() {
+            _ if prev_kind.is_none() => {}
+            "comment" => {
+                super::comment::write_inline(child, source, config, indent_level, output);
+                continue;
+            }
+            _ if prev_kind == Some("comment") => {}
+            _ => output.push(' '),
+        }
+        match child.kind() {
+            "&&" => output.push_str("and"),
+            "||" => output.push_str("or"),
+            _ => super::write(child, source, config, indent_level, output),
+        }
     }
-
-    output
 }
 
 #[cfg(test)]
@@ -46,4 +49,13 @@ mod tests {
 
         assert_eq!(formatted, expected_output);
     }
+
+    #[rstest]
+    #[case("var x = (1 + # c\n\t2)", "var x = (1 +  # c\n\t2)\n")]
+    #[case("var y = (a and # c\n\tb or c)", "var y = (a and  # c\n\tb or c)\n")]
+    fn break_line_after_comments(#[case] source_input: &str, #[case] expected_output: &str) {
+        let formatted = format_code(source_input).unwrap();
+
+        assert_eq!(formatted, expected_output);
+    }
 }
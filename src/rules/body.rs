@@ -1,12 +1,393 @@
+use super::comment;
+use super::member_order::{self, MemberRank};
+use crate::config::Config;
+use crate::node::{get_node_text, is_trailing_comment};
+use crate::text::{TAB_WIDTH, indent_by};
 use tree_sitter::Node;
 
-pub fn apply(node: Node, source: &str, indent_level: usize) -> String {
-    let mut output = String::new();
+struct Statement {
+    text: String,
+    trailing_comment: Option<String>,
+    is_doc_comment: bool,
+    rank: Option<MemberRank>,
+}
+
+/// A statement already written to the output, with `code_end` marking where its
+/// trailing comment starts.
+struct Span {
+    start: usize,
+    code_end: Option<usize>,
+    trailing_comment: Option<String>,
+    is_doc_comment: bool,
+    rank: Option<MemberRank>,
+}
+
+struct Member {
+    rank: MemberRank,
+    statements: Vec<Statement>,
+}
+
+pub fn write(node: Node, source: &str, config: &Config, indent_level: usize, output: &mut String) {
+    let body_start = output.len();
+    let mut spans: Vec<Span> = Vec::new();
 
     for child in node.children(&mut node.walk()) {
-        let text = &super::apply(child, source, indent_level);
-        output.push_str(text);
+        if child.kind() == ";" {
+            if spans.last().is_some() && config.keep_semicolons {
+                output.truncate(output.trim_end_matches('\n').len());
+                output.push_str(";\n");
+            }
+            continue;
+        }
+        if let Some(span) = spans.last_mut().filter(|_| is_trailing_comment(child)) {
+            let comment = get_node_text(child, source);
+            let comment = comment::normalize_comment(comment, config);
+            output.truncate(output.trim_end_matches('\n').len());
+            span.code_end = Some(output.len());
+            comment::push_trailing_comment(output, &comment, 0, config);
+            output.push('\n');
+            span.trailing_comment = Some(comment);
+            continue;
+        }
+        spans.push(Span {
+            start: output.len(),
+            code_end: None,
+            trailing_comment: None,
+            is_doc_comment: child.kind() == "comment"
+                && comment::is_doc_comment(get_node_text(child, source)),
+            rank: member_order::get_rank(child, source),
+        });
+        super::write(child, source, config, indent_level, output);
+    }
+
+    let has_members =
+        node.kind() == "source" || node.parent().map(|p| p.kind()) == Some("class_definition");
+    let is_reordered = config.reorder_members && has_members;
+    if !config.reflow_doc_comments && !config.align_trailing_comments && !is_reordered {
+        return;
+    }
+
+    let mut statements = split_statements(output, body_start, spans);
+
+    if config.reflow_doc_comments {
+        statements = reflow_doc_comments(statements, config, indent_level);
+    }
+
+    if is_reordered {
+        statements = reorder_members(statements, node.kind() == "source");
+    }
+
+    let comment_columns = get_comment_columns(&statements, config);
+
+    for (statement, column) in statements.iter().zip(comment_columns) {
+        output.push_str(&statement.text);
+        if let Some(comment) = &statement.trailing_comment {
+            comment::push_trailing_comment(output, comment, column, config);
+            output.push('\n');
+        }
+    }
+}
+
+fn split_statements(output: &mut String, body_start: usize, spans: Vec<Span>) -> Vec<Statement> {
+    let text = output.split_off(body_start);
+    let ends: Vec<usize> = spans
+        .iter()
+        .skip(1)
+        .map(|span| span.start - body_start)
+        .chain([text.len()])
+        .collect();
+
+    spans
+        .into_iter()
+        .zip(ends)
+        .map(|(span, end)| {
+            let start = span.start - body_start;
+            let text = match span.code_end {
+                Some(code_end) => format!("{}\n", &text[start..code_end - body_start]),
+                None => text[start..end].to_string(),
+            };
+            Statement {
+                text,
+                trailing_comment: span.trailing_comment,
+                is_doc_comment: span.is_doc_comment,
+                rank: span.rank,
+            }
+        })
+        .collect()
+}
+
+fn reflow_doc_comments(
+    statements: Vec<Statement>,
+    config: &Config,
+    indent_level: usize,
+) -> Vec<Statement> {
+    let width = config.line_length.saturating_sub(indent_level * TAB_WIDTH);
+    let mut output = Vec::new();
+    let mut statements = statements.into_iter().peekable();
+
+    while let Some(mut statement) = statements.next() {
+        if statement.is_doc_comment {
+            let mut lines = vec![statement.text.trim().to_string()];
+            while let Some(next) =
+                statements.next_if(|next| next.is_doc_comment && !next.text.starts_with('\n'))
+            {
+                lines.push(next.text.trim().to_string());
+            }
+            statement.text = render_doc_comment(&statement.text, &lines, width, indent_level);
+        }
+        output.push(statement);
+    }
+
+    output
+}
+
+fn render_doc_comment(text: &str, lines: &[String], width: usize, indent_level: usize) -> String {
+    let lines: Vec<&str> = lines.iter().map(String::as_str).collect();
+    let mut output = String::new();
+
+    output.push_str(&"\n".repeat(text.len() - text.trim_start_matches('\n').len()));
+    for line in comment::reflow_doc_comment(&lines, width) {
+        indent_by(&mut output, indent_level);
+        output.push_str(&line);
+        output.push('\n');
     }
 
     output
 }
+
+/// `#region` and `#endregion` stay where they are, and members are only
+/// reordered between them.
+fn reorder_members(statements: Vec<Statement>, is_source: bool) -> Vec<Statement> {
+    let mut output = Vec::new();
+    let mut section = Vec::new();
+
+    for statement in statements {
+        if statement.rank.is_none() && comment::is_region_directive(&statement.text) {
+            output.extend(reorder_section(std::mem::take(&mut section), is_source));
+            output.push(statement);
+        } else {
+            section.push(statement);
+        }
+    }
+    output.extend(reorder_section(section, is_source));
+
+    output
+}
+
+fn reorder_section(statements: Vec<Statement>, is_source: bool) -> Vec<Statement> {
+    let first_gap_lines = statements.first().map_or(0, |statement| {
+        statement.text.len() - statement.text.trim_start_matches('\n').len()
+    });
+    let mut members: Vec<Member> = Vec::new();
+    let mut pending: Vec<Statement> = Vec::new();
+
+    for statement in statements {
+        let Some(rank) = statement.rank else {
+            pending.push(statement);
+            continue;
+        };
+        let split_index = if statement.text.starts_with('\n') {
+            pending.len()
+        } else {
+            pending
+                .iter()
+                .rposition(|s| s.text.starts_with('\n'))
+                .unwrap_or(0)
+        };
+        let mut leading = pending.split_off(split_index);
+        attach_to_last_member(&mut members, &mut pending);
+        leading.push(statement);
+        members.push(Member {
+            rank,
+            statements: leading,
+        });
+    }
+    attach_to_last_member(&mut members, &mut pending);
+
+    let mut members: Vec<(usize, Member)> = members.into_iter().enumerate().collect();
+    members.sort_by_key(|(_, member)| member.rank);
+
+    let mut output = Vec::new();
+    let mut previous: Option<(usize, MemberRank)> = None;
+
+    for (index, mut member) in members {
+        let is_moved = previous.map_or(0, |(i, _)| i + 1) != index;
+        if is_moved {
+            let gap_lines = match previous {
+                None => &"\n".repeat(first_gap_lines),
+                Some(_) if member.rank.is_definition() && is_source => "\n\n",
+                Some((_, rank)) if rank.is_class_header() && member.rank.is_class_header() => "",
+                Some((_, rank)) if member.rank.is_definition() || rank != member.rank => "\n",
+                Some(_) => "",
+            };
+            let first = &mut member.statements[0];
+            first.text = format!("{}{}", gap_lines, first.text.trim_start_matches('\n'));
+        }
+        previous = Some((index, member.rank));
+        output.extend(member.statements);
+    }
+
+    output
+}
+
+fn attach_to_last_member(members: &mut Vec<Member>, statements: &mut Vec<Statement>) {
+    if statements.is_empty() {
+        return;
+    }
+    match members.last_mut() {
+        Some(member) => member.statements.append(statements),
+        None => members.push(Member {
+            rank: MemberRank::Header,
+            statements: std::mem::take(statements),
+        }),
+    }
+}
+
+fn get_comment_columns(statements: &[Statement], config: &Config) -> Vec<usize> {
+    let mut columns = vec![0; statements.len()];
+    if !config.align_trailing_comments {
+        return columns;
+    }
+
+    let mut index = 0;
+    while index < statements.len() {
+        if !is_alignable(&statements[index]) {
+            index += 1;
+            continue;
+        }
+
+        let group_start = index;
+        index += 1;
+        while statements
+            .get(index)
+            .is_some_and(|statement| is_alignable(statement) && !statement.text.starts_with('\n'))
+        {
+            index += 1;
+        }
+
+        let column = statements[group_start..index]
+            .iter()
+            .map(|statement| comment::get_last_line_width(&statement.text))
+            .max()
+            .unwrap_or(0)
+            + config.trailing_comment_spaces;
+        columns[group_start..index].fill(column);
+    }
+
+    columns
+}
+
+fn is_alignable(statement: &Statement) -> bool {
+    let text = statement.text.trim_matches('\n');
+    statement.trailing_comment.is_some() && !text.contains('\n')
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Config, format_code, format_code_with_config};
+    use rstest::*;
+
+    #[rstest]
+    #[case("var a = 1 # x", "var a = 1  # x\n")]
+    #[case("var a = 1       #x", "var a = 1  # x\n")]
+    #[case("var a = 1 # x\nvar b = 2", "var a = 1  # x\nvar b = 2\n")]
+    #[case(
+        "func f():\n\tvar a = 1 # x\n\treturn a # y",
+        "func f():\n\tvar a = 1  # x\n\treturn a  # y\n"
+    )]
+    #[case(
+        "var a = 1 # x\nfunc f():\n\tpass",
+        "var a = 1  # x\n\n\nfunc f():\n\tpass\n"
+    )]
+    #[case(
+        "var a = 1\n# x\nfunc f():\n\tpass",
+        "var a = 1\n# x\nfunc f():\n\tpass\n"
+    )]
+    fn keep_trailing_comments_on_line(#[case] source_input: &str, #[case] expected_output: &str) {
+        let formatted = format_code(source_input).unwrap();
+
+        assert_eq!(formatted, expected_output);
+    }
+
+    #[rstest]
+    #[case("var a = 1; var b = 2", "var a = 1\nvar b = 2\n")]
+    #[case("var a = 1;", "var a = 1\n")]
+    #[case("print(a);print(b);", "print(a)\nprint(b)\n")]
+    #[case("var a = 1; # x", "var a = 1  # x\n")]
+    #[case(
+        "func f():\n\ta = 1; b = 2\n\tprint(a);",
+        "func f():\n\ta = 1\n\tb = 2\n\tprint(a)\n"
+    )]
+    #[case("var a = 1;\nfunc f():\n\tpass", "var a = 1\n\n\nfunc f():\n\tpass\n")]
+    fn split_semicolon_separated_statements(
+        #[case] source_input: &str,
+        #[case] expected_output: &str,
+    ) {
+        let formatted = format_code(source_input).unwrap();
+
+        assert_eq!(formatted, expected_output);
+    }
+
+    #[rstest]
+    #[case("var a = 1; var b = 2", "var a = 1;\nvar b = 2\n")]
+    #[case("var a = 1;", "var a = 1;\n")]
+    #[case("var a = 1; # x", "var a = 1;  # x\n")]
+    fn keep_semicolons_when_configured(#[case] source_input: &str, #[case] expected_output: &str) {
+        let config = Config {
+            keep_semicolons: true,
+            ..Default::default()
+        };
+
+        let formatted = format_code_with_config(source_input, &config).unwrap();
+
+        assert_eq!(formatted, expected_output);
+    }
+
+    #[rstest]
+    #[case("var a = 1 # x", "var a = 1 # x\n")]
+    #[case("var a = 1  # x\nvar bb = 2    # y", "var a = 1 # x\nvar bb = 2 # y\n")]
+    fn use_configured_spaces_before_trailing_comments(
+        #[case] source_input: &str,
+        #[case] expected_output: &str,
+    ) {
+        let config = Config {
+            trailing_comment_spaces: 1,
+            ..Default::default()
+        };
+
+        let formatted = format_code_with_config(source_input, &config).unwrap();
+
+        assert_eq!(formatted, expected_output);
+    }
+
+    #[rstest]
+    #[case(
+        "var a = 1 # x\nvar long_name = 2 # y",
+        "var a = 1          # x\nvar long_name = 2  # y\n"
+    )]
+    #[case(
+        "var a = 1 # x\nvar b = 2\nvar long_name = 3 # y",
+        "var a = 1  # x\nvar b = 2\nvar long_name = 3  # y\n"
+    )]
+    #[case(
+        "var a = 1 # x\n\nvar long_name = 2 # y",
+        "var a = 1  # x\n\nvar long_name = 2  # y\n"
+    )]
+    #[case(
+        "func f():\n\tvar a = 1 # x\n\tvar bb = 2 # y\n\treturn a + bb",
+        "func f():\n\tvar a = 1   # x\n\tvar bb = 2  # y\n\treturn a + bb\n"
+    )]
+    fn align_trailing_comments_when_configured(
+        #[case] source_input: &str,
+        #[case] expected_output: &str,
+    ) {
+        let config = Config {
+            align_trailing_comments: true,
+            ..Default::default()
+        };
+
+        let formatted = format_code_with_config(source_input, &config).unwrap();
+
+        assert_eq!(formatted, expected_output);
+    }
+}
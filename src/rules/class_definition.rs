@@ -1,33 +1,38 @@
-use crate::node::get_gap_lines;
+use crate::config::Config;
+use crate::node::{get_gap_lines, is_trailing_comment};
 use tree_sitter::Node;
 
-pub fn apply(node: Node, source: &str, indent_level: usize) -> String {
-    let gap_lines = get_gap_lines(node, source);
-    let mut output = String::new();
-
-    output.push_str(&gap_lines);
+pub fn write(node: Node, source: &str, config: &Config, indent_level: usize, output: &mut String) {
+    output.push_str(&get_gap_lines(node, source));
 
     for child in node.children(&mut node.walk()) {
-        let child_apply_fn = || super::apply(child, source, indent_level);
-        let (text, space): (&str, &str) = match child.kind() {
-            _ if child.prev_sibling().is_none() => (&child_apply_fn(), ""),
-            ":" => (&child_apply_fn(), ""),
-            "body" => (
-                &format!("\n{}", &super::apply(child, source, indent_level + 1)),
-                "",
-            ),
-            _ => (&child_apply_fn(), " "),
-        };
-        output.push_str(space);
-        output.push_str(text);
+        let prev_kind = child.prev_sibling().map(|ps| ps.kind());
+        match child.kind() {
+            _ if prev_kind.is_none() => {}
+            ":" => {}
+            "comment" if prev_kind == Some(":") && is_trailing_comment(child) => {
+                super::comment::write_trailing(child, source, config, output);
+                continue;
+            }
+            "comment" => {
+                super::comment::write_inline(child, source, config, indent_level, output);
+                continue;
+            }
+            "body" => {
+                output.push('\n');
+                super::write(child, source, config, indent_level + 1, output);
+                continue;
+            }
+            _ if prev_kind == Some("comment") => {}
+            _ => output.push(' '),
+        }
+        super::write(child, source, config, indent_level, output);
     }
-
-    output
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::format_code;
+    use crate::{Config, format_code, format_code_with_config};
     use rstest::*;
 
     #[rstest]
@@ -53,4 +58,23 @@ mod tests {
 
         assert_eq!(formatted, expected_output);
     }
+
+    #[rstest]
+    #[case("class A:      #c\n\tpass", 2, "class A:  # c\n\tpass\n")]
+    #[case("class A: # c\n\tpass", 1, "class A: # c\n\tpass\n")]
+    #[case("class A:# c\n\tpass", 3, "class A:   # c\n\tpass\n")]
+    fn space_trailing_comment_on_header(
+        #[case] source_input: &str,
+        #[case] trailing_comment_spaces: usize,
+        #[case] expected_output: &str,
+    ) {
+        let config = Config {
+            trailing_comment_spaces,
+            ..Default::default()
+        };
+
+        let formatted = format_code_with_config(source_input, &config).unwrap();
+
+        assert_eq!(formatted, expected_output);
+    }
 }
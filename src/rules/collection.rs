@@ -0,0 +1,109 @@
+use crate::config::Config;
+use crate::node::is_trailing_comment;
+use crate::text::{indent_by, line_width};
+use tree_sitter::Node;
+
+/// Line length used while measuring a collection on one line. It can't be set
+/// from `gdfmt.toml`, whose integers stop at `i64::MAX`.
+const MEASURING_LINE_LENGTH: usize = usize::MAX;
+
+pub type WriteFn = fn(Node, &str, &Config, usize, &mut String);
+
+/// Writes the collection on one line when it fits, or one element per line.
+///
+/// The one-line attempt is written in measuring mode, where nested collections
+/// never split and those that must split only leave a line break behind. Every
+/// level is then measured once instead of rendering its children both ways.
+pub fn write(
+    node: Node,
+    source: &str,
+    config: &Config,
+    indent_level: usize,
+    output: &mut String,
+    write_flat: WriteFn,
+    write_fn: WriteFn,
+) {
+    let is_measuring = config.line_length == MEASURING_LINE_LENGTH;
+
+    if must_split(node) {
+        match is_measuring {
+            true => output.push('\n'),
+            false => write_multiline(node, source, config, indent_level, output, write_fn),
+        }
+        return;
+    }
+    if is_measuring {
+        write_flat(node, source, config, indent_level, output);
+        return;
+    }
+
+    let measuring_config = Config {
+        line_length: MEASURING_LINE_LENGTH,
+        ..config.clone()
+    };
+    let start = output.len();
+    write_flat(node, source, &measuring_config, indent_level, output);
+
+    let flat_text = &output[start..];
+    let is_too_wide =
+        get_start_column(&output[..start]) + line_width(flat_text) > config.line_length;
+    if flat_text.contains('\n') || is_too_wide {
+        output.truncate(start);
+        write_multiline(node, source, config, indent_level, output, write_fn);
+    }
+}
+
+fn write_multiline(
+    node: Node,
+    source: &str,
+    config: &Config,
+    indent_level: usize,
+    output: &mut String,
+    write_fn: WriteFn,
+) {
+    for child in node.children(&mut node.walk()) {
+        match child.kind() {
+            "[" | "{" => output.push_str(child.kind()),
+            "]" | "}" => {
+                output.push('\n');
+                indent_by(output, indent_level);
+                output.push_str(child.kind());
+            }
+            "," => {}
+            "comment" if is_trailing_comment(child) => {
+                super::comment::write_trailing(child, source, config, output)
+            }
+            "comment" => {
+                output.push('\n');
+                indent_by(output, indent_level + 1);
+                write_fn(child, source, config, indent_level + 1, output);
+            }
+            _ => {
+                output.push('\n');
+                indent_by(output, indent_level + 1);
+                write_fn(child, source, config, indent_level + 1, output);
+                output.push(',');
+            }
+        }
+    }
+}
+
+/// A comment or a trailing comma after the last element keeps one element per
+/// line.
+fn must_split(node: Node) -> bool {
+    let children: Vec<Node> = node.children(&mut node.walk()).collect();
+    let has_comment = children.iter().any(|c| c.kind() == "comment");
+    let has_trailing_comma = children
+        .iter()
+        .rev()
+        .filter(|c| c.kind() != "comment")
+        .nth(1)
+        .is_some_and(|c| c.kind() == ",");
+
+    has_comment || has_trailing_comma
+}
+
+/// Width of the line already written before the collection.
+fn get_start_column(prefix: &str) -> usize {
+    line_width(prefix.rsplit('\n').next().unwrap_or(prefix))
+}
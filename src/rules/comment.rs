@@ -0,0 +1,367 @@
+use crate::config::Config;
+use crate::formatter::parse_with_errors;
+use crate::node::{get_gap_lines, get_node_text};
+use crate::text::{indent_by, line_width};
+use tree_sitter::Node;
+
+const COMMENT_MARKER: &str = "#";
+const DOC_COMMENT_MARKER: &str = "##";
+const SHEBANG_MARKER: &str = "!";
+const REGION_DIRECTIVES: [&str; 2] = ["region", "endregion"];
+/// Keywords that start a statement without `(` or `=`, like `pass` or `return`.
+const STATEMENT_KEYWORDS: [&str; 16] = [
+    "pass",
+    "return",
+    "break",
+    "continue",
+    "breakpoint",
+    "var",
+    "const",
+    "func",
+    "if",
+    "elif",
+    "else",
+    "for",
+    "while",
+    "match",
+    "await",
+    "signal",
+];
+const CODEBLOCK_TAGS: [(&str, &str); 3] = [
+    ("[codeblock", "[/codeblock]"),
+    ("[codeblocks]", "[/codeblocks]"),
+    ("[code]", "[/code]"),
+];
+
+pub fn write(node: Node, source: &str, config: &Config, indent_level: usize, output: &mut String) {
+    let text = normalize_comment(get_node_text(node, source), config);
+
+    if !super::is_statement(node) {
+        output.push_str(&text);
+        return;
+    }
+
+    let gap_lines = get_gap_lines(node, source);
+
+    output.push_str(&gap_lines);
+    indent_by(output, indent_level);
+    output.push_str(&text);
+    output.push('\n');
+}
+
+/// Writes a comment that follows code on the same line.
+pub fn write_trailing(node: Node, source: &str, config: &Config, output: &mut String) {
+    let text = normalize_comment(get_node_text(node, source), config);
+
+    push_trailing_comment(output, &text, 0, config);
+}
+
+/// Writes a comment found inside an expression, then breaks the line with a
+/// continuation indent so the code after it isn't commented out.
+pub fn write_inline(
+    node: Node,
+    source: &str,
+    config: &Config,
+    indent_level: usize,
+    output: &mut String,
+) {
+    let is_before_closing = node
+        .next_sibling()
+        .is_some_and(|next| matches!(next.kind(), ")" | "]" | "}"));
+
+    write_trailing(node, source, config, output);
+    output.push('\n');
+    indent_by(output, indent_level + usize::from(!is_before_closing));
+}
+
+/// Pushes `comment` after the code on the last line of `output`, at `column` or
+/// `trailing_comment_spaces` past the code, whichever is further.
+pub fn push_trailing_comment(output: &mut String, comment: &str, column: usize, config: &Config) {
+    output.truncate(output.trim_end_matches(['\n', ' ']).len());
+    let width = get_last_line_width(output);
+    output.push_str(&" ".repeat(column.max(width + config.trailing_comment_spaces) - width));
+    output.push_str(comment);
+}
+
+pub fn get_last_line_width(text: &str) -> usize {
+    let text = text.trim_end_matches('\n');
+    text.rsplit('\n').next().unwrap_or(text).chars().count()
+}
+
+pub fn normalize_comment(text: &str, config: &Config) -> String {
+    let text = text.trim();
+    let (marker, content) = match text.strip_prefix(DOC_COMMENT_MARKER) {
+        Some(content) if !content.starts_with(COMMENT_MARKER) => (DOC_COMMENT_MARKER, content),
+        Some(_) => return text.to_string(),
+        None => (
+            COMMENT_MARKER,
+            text.strip_prefix(COMMENT_MARKER).unwrap_or(text),
+        ),
+    };
+
+    let needs_space = match content.chars().next() {
+        None => false,
+        Some(c) if c.is_whitespace() => false,
+        _ if marker == DOC_COMMENT_MARKER => true,
+        _ if content.starts_with(SHEBANG_MARKER) || is_region_keyword(content) => false,
+        _ if config.preserve_commented_out_code => !is_code(content),
+        _ => true,
+    };
+
+    let mut output = String::new();
+
+    output.push_str(marker);
+    if needs_space {
+        output.push(' ');
+    }
+    output.push_str(content);
+
+    output
+}
+
+pub fn is_region_directive(text: &str) -> bool {
+    text.trim()
+        .strip_prefix(COMMENT_MARKER)
+        .is_some_and(is_region_keyword)
+}
+
+/// `region` and `endregion` as whole words, so `#regional note` is a comment.
+fn is_region_keyword(content: &str) -> bool {
+    REGION_DIRECTIVES.iter().any(|directive| {
+        content
+            .strip_prefix(directive)
+            .is_some_and(|rest| rest.chars().next().is_none_or(char::is_whitespace))
+    })
+}
+
+pub fn is_doc_comment(text: &str) -> bool {
+    text.starts_with(DOC_COMMENT_MARKER)
+        && !text[DOC_COMMENT_MARKER.len()..].starts_with(COMMENT_MARKER)
+}
+
+pub fn reflow_doc_comment(lines: &[&str], width: usize) -> Vec<String> {
+    let mut output = Vec::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut closing_tag: Option<&str> = None;
+
+    for line in lines {
+        let content = line[DOC_COMMENT_MARKER.len()..].trim_end();
+        let trimmed = content.trim_start();
+
+        if let Some(tag) = closing_tag {
+            if trimmed.contains(tag) {
+                closing_tag = None;
+            }
+            output.push(line.to_string());
+            continue;
+        }
+
+        let opening_tag = CODEBLOCK_TAGS
+            .iter()
+            .find(|(open, close)| trimmed.starts_with(open) && !trimmed.contains(close));
+        let is_verbatim = trimmed.is_empty()
+            || trimmed.starts_with('@')
+            || content.starts_with("  ")
+            || content.starts_with('\t')
+            || opening_tag.is_some();
+
+        if is_list_item(trimmed) {
+            output.extend(fill_paragraph(&paragraph, width));
+            paragraph.clear();
+        }
+        if !is_verbatim {
+            paragraph.push(trimmed);
+            continue;
+        }
+
+        output.extend(fill_paragraph(&paragraph, width));
+        paragraph.clear();
+        output.push(line.to_string());
+        closing_tag = opening_tag.map(|(_, close)| *close);
+    }
+    output.extend(fill_paragraph(&paragraph, width));
+
+    output
+}
+
+/// Whether the line starts a `-`, `*` or `1.` list item, which begins a new
+/// paragraph instead of joining the previous line.
+fn is_list_item(text: &str) -> bool {
+    if text.starts_with("- ") || text.starts_with("* ") {
+        return true;
+    }
+    let digits = text.len() - text.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+
+    digits > 0 && text[digits..].starts_with(". ")
+}
+
+fn fill_paragraph(paragraph: &[&str], width: usize) -> Vec<String> {
+    let mut output = Vec::new();
+    let mut line = String::new();
+
+    for word in paragraph.iter().flat_map(|text| split_words(text)) {
+        if !line.is_empty() && line_width(&line) + 1 + line_width(word) > width {
+            output.push(line);
+            line = String::new();
+        }
+        if line.is_empty() {
+            line.push_str(DOC_COMMENT_MARKER);
+        }
+        line.push(' ');
+        line.push_str(word);
+    }
+    if !line.is_empty() {
+        output.push(line);
+    }
+
+    output
+}
+
+fn split_words(text: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    let mut depth = 0;
+    let mut start = None;
+
+    for (index, c) in text.char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' => depth -= 1,
+            _ if c.is_whitespace() && depth <= 0 => {
+                if let Some(word_start) = start.take() {
+                    words.push(&text[word_start..index]);
+                }
+                continue;
+            }
+            _ => {}
+        }
+        start.get_or_insert(index);
+    }
+    if let Some(word_start) = start {
+        words.push(&text[word_start..]);
+    }
+
+    words
+}
+
+fn is_code(text: &str) -> bool {
+    let first_word = text.split_whitespace().next().unwrap_or_default();
+    if !text.contains(['(', '=']) && !STATEMENT_KEYWORDS.contains(&first_word) {
+        return false;
+    }
+
+    let Ok(tree) = parse_with_errors(text) else {
+        return false;
+    };
+    let root_node = tree.root_node();
+    let is_single_word = root_node.named_child(0).is_some_and(|statement| {
+        statement.kind() == "expression_statement"
+            && statement.named_child(0).map(|n| n.kind()) == Some("identifier")
+    });
+
+    !root_node.has_error() && !is_single_word
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Config, format_code, format_code_with_config};
+    use rstest::*;
+
+    #[rstest]
+    #[case("# comment", "# comment\n")]
+    #[case("#comment", "# comment\n")]
+    #[case("#TODO: fix this", "# TODO: fix this\n")]
+    #[case("#\tcomment", "#\tcomment\n")]
+    #[case("#  comment", "#  comment\n")]
+    #[case("#", "#\n")]
+    #[case("# comment  \t", "# comment\n")]
+    #[case("##doc comment", "## doc comment\n")]
+    #[case("## doc comment", "## doc comment\n")]
+    #[case("##", "##\n")]
+    #[case("##   indented", "##   indented\n")]
+    #[case("#############", "#############\n")]
+    #[case("func a():\n\t#comment\n\tpass", "func a():\n\t# comment\n\tpass\n")]
+    fn ensure_space_after_marker(#[case] source_input: &str, #[case] expected_output: &str) {
+        let formatted = format_code(source_input).unwrap();
+
+        assert_eq!(formatted, expected_output);
+    }
+
+    #[rstest]
+    #[case("#region Movement", "#region Movement\n")]
+    #[case("#endregion", "#endregion\n")]
+    #[case("#!/usr/bin/env -S godot -s", "#!/usr/bin/env -S godot -s\n")]
+    #[case("#regional note", "# regional note\n")]
+    #[case("#endregions", "# endregions\n")]
+    fn keep_directives(#[case] source_input: &str, #[case] expected_output: &str) {
+        let formatted = format_code(source_input).unwrap();
+
+        assert_eq!(formatted, expected_output);
+    }
+
+    #[rstest]
+    #[case("#print(a)", "#print(a)\n")]
+    #[case("#var a = 1", "#var a = 1\n")]
+    #[case("#a = 1  ", "#a = 1\n")]
+    #[case("#pass", "#pass\n")]
+    #[case("#return a", "#return a\n")]
+    fn keep_commented_out_code(#[case] source_input: &str, #[case] expected_output: &str) {
+        let formatted = format_code(source_input).unwrap();
+
+        assert_eq!(formatted, expected_output);
+    }
+
+    #[rstest]
+    #[case("#print(a)", "# print(a)\n")]
+    #[case("#var a = 1", "# var a = 1\n")]
+    #[case("#region Movement", "#region Movement\n")]
+    fn space_commented_out_code_when_configured(
+        #[case] source_input: &str,
+        #[case] expected_output: &str,
+    ) {
+        let config = Config {
+            preserve_commented_out_code: false,
+            ..Default::default()
+        };
+
+        let formatted = format_code_with_config(source_input, &config).unwrap();
+
+        assert_eq!(formatted, expected_output);
+    }
+
+    #[rstest]
+    #[case("## a b c d e f", "## a b c d\n## e f\n")]
+    #[case("## a b\n## c\n## d e f", "## a b c d\n## e f\n")]
+    #[case("## a b c d e\n##\n## f", "## a b c d\n## e\n##\n## f\n")]
+    #[case("## a [b c d] e", "## a\n## [b c d]\n## e\n")]
+    #[case("## [method Node.x] a", "## [method Node.x]\n## a\n")]
+    #[case(
+        "## a b c d e\n## [codeblock]\n## var x = 1 + 2\n## [/codeblock]",
+        "## a b c d\n## e\n## [codeblock]\n## var x = 1 + 2\n## [/codeblock]\n"
+    )]
+    #[case("## a\n## @tutorial: b c d e", "## a\n## @tutorial: b c d e\n")]
+    #[case("## a\n\n## b", "## a\n\n## b\n")]
+    #[case("## - a yes\n## - b", "## - a yes\n## - b\n")]
+    #[case("## a\n## * b\n## 1. c\n## 10. d", "## a\n## * b\n## 1. c\n## 10. d\n")]
+    #[case("## - a b c d e\n## - f", "## - a b c\n## d e\n## - f\n")]
+    #[case("## a\n## -b\n## 1.5", "## a -b\n## 1.5\n")]
+    #[case("## a\n## 1.5", "## a 1.5\n")]
+    #[case("# a b c d e f", "# a b c d e f\n")]
+    #[case(
+        "func f():\n\t## a b c d e\n\tpass",
+        "func f():\n\t## a b\n\t## c d\n\t## e\n\tpass\n"
+    )]
+    fn reflow_doc_comments_when_configured(
+        #[case] source_input: &str,
+        #[case] expected_output: &str,
+    ) {
+        let config = Config {
+            line_length: 10,
+            reflow_doc_comments: true,
+            ..Default::default()
+        };
+
+        let formatted = format_code_with_config(source_input, &config).unwrap();
+
+        assert_eq!(formatted, expected_output);
+    }
+}
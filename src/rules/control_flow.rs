@@ -0,0 +1,188 @@
+use crate::config::Config;
+use crate::node::{get_gap_lines, is_trailing_comment};
+use crate::text::{indent_by, line_width};
+use tree_sitter::Node;
+
+pub fn write(node: Node, source: &str, config: &Config, indent_level: usize, output: &mut String) {
+    match node.kind() {
+        "if_statement" | "for_statement" | "while_statement" | "match_statement" => {
+            output.push_str(&get_gap_lines(node, source));
+            write_block_rules(node, source, config, indent_level, output);
+        }
+        "elif_clause" | "else_clause" => {
+            write_block_rules(node, source, config, indent_level, output)
+        }
+        "match_body" => write_match_body_rules(node, source, config, indent_level, output),
+        _ => super::write(node, source, config, indent_level, output),
+    }
+}
+
+fn write_block_rules(
+    node: Node,
+    source: &str,
+    config: &Config,
+    indent_level: usize,
+    output: &mut String,
+) {
+    let start = output.len();
+
+    indent_by(output, indent_level);
+
+    for child in node.children(&mut node.walk()) {
+        match child.kind() {
+            _ if child.prev_sibling().is_none() => {}
+            ":" | "," | "elif_clause" | "else_clause" => {}
+            "body" => {
+                write_body_rules(child, source, config, indent_level, start, output);
+                continue;
+            }
+            "match_body" => {
+                output.push('\n');
+                write(child, source, config, indent_level + 1, output);
+                continue;
+            }
+            "comment" if is_trailing_comment(child) => {
+                super::comment::write_trailing(child, source, config, output);
+                continue;
+            }
+            "comment" => {
+                write_own_line_comment_rules(child, node, source, config, indent_level, output);
+                continue;
+            }
+            _ => output.push(' '),
+        }
+        write(child, source, config, indent_level, output);
+    }
+
+    if !output.ends_with('\n') {
+        output.push('\n');
+    }
+}
+
+fn write_own_line_comment_rules(
+    node: Node,
+    parent: Node,
+    source: &str,
+    config: &Config,
+    indent_level: usize,
+    output: &mut String,
+) {
+    let is_nested = node.start_position().column > parent.start_position().column;
+
+    indent_by(output, indent_level + usize::from(is_nested));
+    write(node, source, config, indent_level, output);
+    output.push('\n');
+}
+
+fn write_body_rules(
+    node: Node,
+    source: &str,
+    config: &Config,
+    indent_level: usize,
+    header_start: usize,
+    output: &mut String,
+) {
+    if let Some(text) = get_one_line_body(node, source, config, &output[header_start..]) {
+        output.push(' ');
+        output.push_str(&text);
+        output.push('\n');
+        return;
+    }
+
+    output.push('\n');
+    super::write(node, source, config, indent_level + 1, output);
+}
+
+fn get_one_line_body(node: Node, source: &str, config: &Config, header: &str) -> Option<String> {
+    let colon = node.prev_sibling().filter(|n| n.kind() == ":")?;
+    let statement = node.named_child(0)?;
+    let is_one_line_in_source = colon.end_position().row == statement.start_position().row;
+    if !config.keep_short_one_line_blocks || !is_one_line_in_source || node.named_child_count() != 1
+    {
+        return None;
+    }
+
+    let mut text = String::new();
+    super::write(node, source, config, 0, &mut text);
+    let text = text.trim();
+    let header_line = header.rsplit('\n').next().unwrap_or(header);
+    let fits = line_width(header_line) + 1 + line_width(text) <= config.line_length;
+
+    (fits && !text.contains('\n')).then(|| text.to_string())
+}
+
+fn write_match_body_rules(
+    node: Node,
+    source: &str,
+    config: &Config,
+    indent_level: usize,
+    output: &mut String,
+) {
+    for child in node.children(&mut node.walk()) {
+        output.push_str(&get_gap_lines(child, source));
+        match child.kind() {
+            "pattern_section" => write_block_rules(child, source, config, indent_level, output),
+            _ => super::write(child, source, config, indent_level, output),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Config, format_code, format_code_with_config};
+    use rstest::*;
+
+    #[rstest]
+    #[case("if dead: return", "if dead:\n\treturn\n")]
+    #[case("if  a  :\n\tpass", "if a:\n\tpass\n")]
+    #[case(
+        "if a: pass\nelif b : pass\nelse : pass",
+        "if a:\n\tpass\nelif b:\n\tpass\nelse:\n\tpass\n"
+    )]
+    #[case("if a&&b: pass", "if a and b:\n\tpass\n")]
+    #[case("for x in xs: print(x)", "for x in xs:\n\tprint(x)\n")]
+    #[case("for i:int in range(3): pass", "for i: int in range(3):\n\tpass\n")]
+    #[case("while  true :\n\tbreak", "while true:\n\tbreak\n")]
+    #[case("for x in xs:\n\tcontinue", "for x in xs:\n\tcontinue\n")]
+    #[case("if a: b = 1; c = 2", "if a:\n\tb = 1\n\tc = 2\n")]
+    #[case("if a: # c\n\tpass", "if a:  # c\n\tpass\n")]
+    #[case(
+        "func f():\n\tif a: return\n\tfor x in xs:\n\t\tif x: print(x)",
+        "func f():\n\tif a:\n\t\treturn\n\tfor x in xs:\n\t\tif x:\n\t\t\tprint(x)\n"
+    )]
+    #[case(
+        "match x:\n\t1, 2: print(1)\n\tvar y when y > 1:\n\t\tpass\n\n\n\t_: pass",
+        "match x:\n\t1, 2:\n\t\tprint(1)\n\tvar y when y > 1:\n\t\tpass\n\n\t_:\n\t\tpass\n"
+    )]
+    fn expand_one_line_blocks(#[case] source_input: &str, #[case] expected_output: &str) {
+        let formatted = format_code(source_input).unwrap();
+
+        assert_eq!(formatted, expected_output);
+    }
+
+    #[rstest]
+    #[case("if dead: return", "if dead: return\n")]
+    #[case("if dead:\n\treturn", "if dead:\n\treturn\n")]
+    #[case("if a: pass\nelse: b()", "if a: pass\nelse: b()\n")]
+    #[case("for x in xs: print(x)", "for x in xs: print(x)\n")]
+    #[case("if a: b = 1; c = 2", "if a:\n\tb = 1\n\tc = 2\n")]
+    #[case(
+        "if a_long_condition: print(a_long_argument)",
+        "if a_long_condition:\n\tprint(a_long_argument)\n"
+    )]
+    #[case("match x:\n\t1: pass", "match x:\n\t1: pass\n")]
+    fn keep_short_one_line_blocks_when_configured(
+        #[case] source_input: &str,
+        #[case] expected_output: &str,
+    ) {
+        let config = Config {
+            keep_short_one_line_blocks: true,
+            line_length: 30,
+            ..Default::default()
+        };
+
+        let formatted = format_code_with_config(source_input, &config).unwrap();
+
+        assert_eq!(formatted, expected_output);
+    }
+}
@@ -1,55 +1,71 @@
+use super::collection;
+use crate::config::Config;
 use tree_sitter::Node;
 
-pub fn apply(node: Node, source: &str, indent_level: usize) -> String {
+pub fn write(node: Node, source: &str, config: &Config, indent_level: usize, output: &mut String) {
     match node.kind() {
-        "dictionary" => apply_dictionary_rules(node, source, indent_level),
-        "pair" => apply_pair_rules(node, source, indent_level),
-        _ => super::apply(node, source, indent_level),
+        "dictionary" => collection::write(
+            node,
+            source,
+            config,
+            indent_level,
+            output,
+            write_dictionary_rules,
+            write,
+        ),
+        "pair" => write_pair_rules(node, source, config, indent_level, output),
+        _ => super::write(node, source, config, indent_level, output),
     }
 }
 
-fn apply_dictionary_rules(node: Node, source: &str, indent_level: usize) -> String {
-    let mut output = String::new();
-
+fn write_dictionary_rules(
+    node: Node,
+    source: &str,
+    config: &Config,
+    indent_level: usize,
+    output: &mut String,
+) {
     for child in node.children(&mut node.walk()) {
         let next_kind = child.next_sibling().map(|ns| ns.kind());
         let prev_kind = child.prev_sibling().map(|ps| ps.kind());
-        let child_apply_fn = || apply(child, source, indent_level);
-        let (text, space): (&str, &str) = match child.kind() {
-            "{" => (&child_apply_fn(), ""),
-            "}" if prev_kind == Some("{") => (&child_apply_fn(), ""),
-            "}" => (&child_apply_fn(), " "),
-            "," if next_kind == Some("}") => ("", ""),
-            "," => (&child_apply_fn(), ""),
-            _ => (&child_apply_fn(), " "),
-        };
-        output.push_str(space);
-        output.push_str(text);
+        match child.kind() {
+            "{" => {}
+            "}" if prev_kind == Some("{") => {}
+            "}" => output.push(' '),
+            "," if next_kind == Some("}") => continue,
+            "," => {}
+            _ => output.push(' '),
+        }
+        write(child, source, config, indent_level, output);
     }
-
-    output
 }
 
-fn apply_pair_rules(node: Node, source: &str, indent_level: usize) -> String {
-    let mut output = String::new();
-
+fn write_pair_rules(
+    node: Node,
+    source: &str,
+    config: &Config,
+    indent_level: usize,
+    output: &mut String,
+) {
     for child in node.children(&mut node.walk()) {
-        let child_apply_fn = || apply(child, source, indent_level);
-        let (text, space): (&str, &str) = match child.kind() {
-            _ if child.prev_sibling().is_none() => (&child_apply_fn(), ""),
-            ":" => (&child_apply_fn(), ""),
-            _ => (&child_apply_fn(), " "),
-        };
-        output.push_str(space);
-        output.push_str(text);
+        let prev_kind = child.prev_sibling().map(|ps| ps.kind());
+        match child.kind() {
+            _ if prev_kind.is_none() => {}
+            ":" => {}
+            "comment" => {
+                super::comment::write_inline(child, source, config, indent_level, output);
+                continue;
+            }
+            _ if prev_kind == Some("comment") => {}
+            _ => output.push(' '),
+        }
+        write(child, source, config, indent_level, output);
     }
-
-    output
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::format_code;
+    use crate::{Config, format_code, format_code_with_config};
     use rstest::*;
 
     #[rstest]
@@ -68,14 +84,56 @@ mod tests {
     #[case("var d={}", "var d = {}\n")]
     #[case(r#"var e={"one":1}"#, "var e = { \"one\": 1 }\n")]
     #[case(r#"var f={ "one":1 }"#, "var f = { \"one\": 1 }\n")]
-    #[case(r#"var g={ "one":1, }"#, "var g = { \"one\": 1 }\n")]
     #[case("var h = { 1: 1 }", "var h = { 1: 1 }\n")]
     #[case("var i = {1:1}", "var i = { 1: 1 }\n")]
     #[case("var j = {1:1,2:2}", "var j = { 1: 1, 2: 2 }\n")]
-    #[case("var k = {1:1,2:2,}", "var k = { 1: 1, 2: 2 }\n")]
     fn enforce_spacing_rules(#[case] source_input: &str, #[case] expected_output: &str) {
         let formatted = format_code(source_input).unwrap();
 
         assert_eq!(formatted, expected_output);
     }
+
+    #[rstest]
+    #[case(r#"var g={ "one":1, }"#, "var g = {\n\t\"one\": 1,\n}\n")]
+    #[case("var k = {1:1,2:2,}", "var k = {\n\t1: 1,\n\t2: 2,\n}\n")]
+    #[case("var l = {\n\t1: 1,\n\t2: 2\n}", "var l = { 1: 1, 2: 2 }\n")]
+    #[case(
+        "var m = {1: {2: 2,}, 3: 3}",
+        "var m = {\n\t1: {\n\t\t2: 2,\n\t},\n\t3: 3,\n}\n"
+    )]
+    #[case("var n = {1: [2,]}", "var n = {\n\t1: [\n\t\t2,\n\t],\n}\n")]
+    #[case(
+        "var o = {\n\t1: 1, # one\n\t2: 2,\n}",
+        "var o = {\n\t1: 1,  # one\n\t2: 2,\n}\n"
+    )]
+    fn split_pairs_with_trailing_comma(#[case] source_input: &str, #[case] expected_output: &str) {
+        let formatted = format_code(source_input).unwrap();
+
+        assert_eq!(formatted, expected_output);
+    }
+
+    #[rstest]
+    #[case("var a = { 1: 1 }", "var a = { 1: 1 }\n")]
+    #[case("var b = { 1: 1, 2: 2 }", "var b = {\n\t1: 1,\n\t2: 2,\n}\n")]
+    fn split_pairs_exceeding_line_length(
+        #[case] source_input: &str,
+        #[case] expected_output: &str,
+    ) {
+        let config = Config {
+            line_length: 18,
+            ..Default::default()
+        };
+
+        let formatted = format_code_with_config(source_input, &config).unwrap();
+
+        assert_eq!(formatted, expected_output);
+    }
+
+    #[rstest]
+    #[case("var a = {1: # c\n\t2}", "var a = {\n\t1:  # c\n\t\t2,\n}\n")]
+    fn break_line_after_comments(#[case] source_input: &str, #[case] expected_output: &str) {
+        let formatted = format_code(source_input).unwrap();
+
+        assert_eq!(formatted, expected_output);
+    }
 }
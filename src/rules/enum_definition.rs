@@ -1,84 +1,105 @@
-use crate::{node::get_gap_lines, text::indent_by};
+use crate::config::Config;
+use crate::node::{get_gap_lines, is_trailing_comment};
+use crate::text::indent_by;
 use tree_sitter::Node;
 
-pub fn apply(node: Node, source: &str, indent_level: usize) -> String {
+pub fn write(node: Node, source: &str, config: &Config, indent_level: usize, output: &mut String) {
     match node.kind() {
-        "enum_definition" => apply_enum_definition_rules(node, source, indent_level),
-        "enumerator_list" => apply_enumerator_list_rules(node, source, indent_level),
-        "enumerator" => apply_enumerator_rules(node, source, indent_level),
-        _ => super::apply(node, source, indent_level),
+        "enum_definition" => {
+            write_enum_definition_rules(node, source, config, indent_level, output)
+        }
+        "enumerator_list" => {
+            write_enumerator_list_rules(node, source, config, indent_level, output)
+        }
+        "enumerator" => write_enumerator_rules(node, source, config, indent_level, output),
+        _ => super::write(node, source, config, indent_level, output),
     }
 }
 
-fn apply_enum_definition_rules(node: Node, source: &str, indent_level: usize) -> String {
+fn write_enum_definition_rules(
+    node: Node,
+    source: &str,
+    config: &Config,
+    indent_level: usize,
+    output: &mut String,
+) {
     let gap_lines = get_gap_lines(node, source);
-    let mut output = String::new();
 
     output.push_str(&gap_lines);
-    indent_by(&mut output, indent_level);
+    indent_by(output, indent_level);
 
     for child in node.children(&mut node.walk()) {
-        let child_apply_fn = || apply(child, source, indent_level);
-        let (text, space): (&str, &str) = match child.kind() {
-            "enum" => (&child_apply_fn(), ""),
-            _ => (&child_apply_fn(), " "),
-        };
-        output.push_str(space);
-        output.push_str(text);
+        match child.kind() {
+            "enum" => {}
+            _ => output.push(' '),
+        }
+        write(child, source, config, indent_level, output);
     }
 
     output.push('\n');
-
-    output
 }
 
-fn apply_enumerator_list_rules(node: Node, source: &str, indent_level: usize) -> String {
-    let mut output = String::new();
-
+fn write_enumerator_list_rules(
+    node: Node,
+    source: &str,
+    config: &Config,
+    indent_level: usize,
+    output: &mut String,
+) {
     for child in node.children(&mut node.walk()) {
         let prev_kind = child.prev_sibling().map(|ps| ps.kind());
-        let child_apply_fn = || apply(child, source, indent_level);
-        let (text, space): (&str, &str) = match child.kind() {
-            "{" if child.prev_sibling().is_none() => (&child_apply_fn(), ""),
+        match child.kind() {
+            "{" if child.prev_sibling().is_none() => {}
             "enumerator" => {
                 output.push('\n');
-                indent_by(&mut output, indent_level + 1);
-                (&child_apply_fn(), "")
+                indent_by(output, indent_level + 1);
+            }
+            "," => {}
+            "comment" => {
+                if prev_kind == Some("enumerator") {
+                    output.push(',');
+                }
+                if is_trailing_comment(child) {
+                    super::comment::write_trailing(child, source, config, output);
+                    continue;
+                }
+                output.push('\n');
+                indent_by(output, indent_level + 1);
             }
-            "," => (&child_apply_fn(), ""),
-            "}" if prev_kind == Some("{") => (&child_apply_fn(), ""),
+            "}" if prev_kind == Some("{") => {}
             "}" => {
                 if prev_kind == Some("enumerator") {
                     output.push(',');
                 }
                 output.push('\n');
-                indent_by(&mut output, indent_level);
-                (&child_apply_fn(), "")
+                indent_by(output, indent_level);
             }
-            _ => (&child_apply_fn(), ""),
-        };
-        output.push_str(space);
-        output.push_str(text);
+            _ => {}
+        }
+        write(child, source, config, indent_level, output);
     }
-
-    output
 }
 
-pub fn apply_enumerator_rules(node: Node, source: &str, indent_level: usize) -> String {
-    let mut output = String::new();
-
+pub fn write_enumerator_rules(
+    node: Node,
+    source: &str,
+    config: &Config,
+    indent_level: usize,
+    output: &mut String,
+) {
     for child in node.children(&mut node.walk()) {
-        let child_apply_fn = || apply(child, source, indent_level);
-        let (text, space): (&str, &str) = match child.kind() {
-            _ if child.prev_sibling().is_none() => (&child_apply_fn(), ""),
-            "=" => (&child_apply_fn(), " "),
-            _ => (&child_apply_fn(), " "),
-        };
-        output.push_str(space);
-        output.push_str(text);
+        let prev_kind = child.prev_sibling().map(|ps| ps.kind());
+        match child.kind() {
+            _ if prev_kind.is_none() => {}
+            "comment" => {
+                super::comment::write_inline(child, source, config, indent_level + 1, output);
+                continue;
+            }
+            _ if prev_kind == Some("comment") => {}
+            _ => output.push(' '),
+        }
+        write(child, source, config, indent_level, output);
     }
-
-    output
 }
 
 #[cfg(test)]
@@ -99,9 +120,19 @@ mod tests {
         "class A:\n\tenum D{F = 0,}",
         "class A:\n\tenum D {\n\t\tF = 0,\n\t}\n"
     )]
+    #[case("enum G {A, # a\n B #b\n}", "enum G {\n\tA,  # a\n\tB,  # b\n}\n")]
+    #[case("enum H {\n# a\nA,\n}", "enum H {\n\t# a\n\tA,\n}\n")]
     fn enforce_spacing_rules(#[case] source_input: &str, #[case] expected_output: &str) {
         let formatted = format_code(source_input).unwrap();
 
         assert_eq!(formatted, expected_output);
     }
+
+    #[rstest]
+    #[case("enum E { A = # c\n\t1 }", "enum E {\n\tA =  # c\n\t\t1,\n}\n")]
+    fn break_line_after_comments(#[case] source_input: &str, #[case] expected_output: &str) {
+        let formatted = format_code(source_input).unwrap();
+
+        assert_eq!(formatted, expected_output);
+    }
 }
@@ -1,18 +1,59 @@
+use super::integer::format_digits;
+use crate::config::Config;
 use crate::node::get_node_text;
 use tree_sitter::Node;
 
-pub fn apply(node: Node, source: &str, _indent_level: usize) -> String {
-    let text = get_node_text(node, source);
-    let leading_zero = if text.starts_with(".") { "0" } else { "" };
-    let trailing_zero = if text.ends_with(".") { "0" } else { "" };
+const EXPONENT_MARKER: char = 'e';
 
-    let mut output = String::new();
+pub fn write(node: Node, source: &str, config: &Config, _indent_level: usize, output: &mut String) {
+    let text = get_node_text(node, source).to_lowercase();
+    let (mantissa, exponent) = match text.split_once(EXPONENT_MARKER) {
+        Some((mantissa, exponent)) => (mantissa, Some(exponent.trim_start_matches('+'))),
+        None => (text.as_str(), None),
+    };
+    let (integer_part, fraction_part) = match mantissa.split_once('.') {
+        Some((integer_part, fraction_part)) => (integer_part, Some(fraction_part)),
+        None => (mantissa, None),
+    };
 
-    output.push_str(leading_zero);
-    output.push_str(text);
-    output.push_str(trailing_zero);
+    output.push_str(&format_integer_part(integer_part, config));
+    if let Some(fraction_part) = fraction_part {
+        output.push('.');
+        output.push_str(&format_fraction_part(fraction_part, config));
+    }
+    if let Some(exponent) = exponent {
+        output.push(EXPONENT_MARKER);
+        output.push_str(exponent);
+    }
+}
+
+fn format_integer_part(text: &str, config: &Config) -> String {
+    if text.is_empty() {
+        return "0".to_string();
+    }
+
+    format_decimal_digits(text, config)
+}
+
+fn format_fraction_part(text: &str, config: &Config) -> String {
+    if text.is_empty() {
+        return "0".to_string();
+    }
 
-    output
+    let reversed: String = text.chars().rev().collect();
+    format_decimal_digits(&reversed, config)
+        .chars()
+        .rev()
+        .collect()
+}
+
+fn format_decimal_digits(text: &str, config: &Config) -> String {
+    format_digits(
+        text,
+        config.decimal_group_length,
+        config.number_separator_threshold,
+        config,
+    )
 }
 
 #[cfg(test)]
@@ -28,4 +69,33 @@ mod tests {
 
         assert_eq!(formatted, expected_output);
     }
+
+    #[rstest]
+    #[case("var a = 1E5", "var a = 1e5\n")]
+    #[case("var b = 1.e-3", "var b = 1.0e-3\n")]
+    #[case("var c = .5E3", "var c = 0.5e3\n")]
+    #[case("var d = 1e+10", "var d = 1e10\n")]
+    #[case("var e = 2.5e-10", "var e = 2.5e-10\n")]
+    fn normalize_exponent_notation(#[case] source_input: &str, #[case] expected_output: &str) {
+        let formatted = format_code(source_input).unwrap();
+
+        assert_eq!(formatted, expected_output);
+    }
+
+    #[rstest]
+    #[case("var a = 123456.5", "var a = 123456.5\n")]
+    #[case("var b = 1234567.5", "var b = 1_234_567.5\n")]
+    #[case("var c = 0.1234567", "var c = 0.123_456_7\n")]
+    #[case("var d = 12345678.12345678", "var d = 12_345_678.123_456_78\n")]
+    #[case("var e = 1_000.5", "var e = 1000.5\n")]
+    #[case("var f = 1_2_3_4_5_6_7.0", "var f = 1_234_567.0\n")]
+    #[case("var g = 1234567e5", "var g = 1_234_567e5\n")]
+    fn use_underscore_to_make_large_numbers_more_readable(
+        #[case] source_input: &str,
+        #[case] expected_output: &str,
+    ) {
+        let formatted = format_code(source_input).unwrap();
+
+        assert_eq!(formatted, expected_output);
+    }
 }
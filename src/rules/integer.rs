@@ -1,48 +1,73 @@
+use crate::config::{Config, HexDigitCase, NumberSeparators};
 use crate::node::get_node_text;
 use tree_sitter::Node;
 
-const MIN_LENGTH: usize = 6;
-const DEFAULT_GROUP_LENGTH: usize = 3;
 const BINARY_PREFIX: &str = "0b";
-const BINARY_GROUP_LENGTH: usize = 4;
 const HEXADECIMAL_PREFIX: &str = "0x";
-const HEXADECIMAL_GROUP_LENGTH: usize = 4;
+const SEPARATOR: char = '_';
 
-pub fn apply(node: Node, source: &str, _indent_level: usize) -> String {
-    let text = &get_node_text(node, source).to_lowercase();
+pub fn write(node: Node, source: &str, config: &Config, _indent_level: usize, output: &mut String) {
+    let text = get_node_text(node, source);
+    let prefix = text.get(..2).map(str::to_lowercase).unwrap_or_default();
 
-    let (prefix, text): (&str, &str) = match (
-        text.starts_with(BINARY_PREFIX),
-        text.starts_with(HEXADECIMAL_PREFIX),
-    ) {
-        (true, _) => (
+    let (prefix, text): (&str, &str) = match prefix.as_str() {
+        BINARY_PREFIX => (
             BINARY_PREFIX,
-            &format_with_underscores(text.trim_start_matches(BINARY_PREFIX), BINARY_GROUP_LENGTH),
+            &format_digits(&text[2..], config.binary_group_length, 0, config),
         ),
-        (_, true) => (
+        HEXADECIMAL_PREFIX => (
             HEXADECIMAL_PREFIX,
-            &format_with_underscores(
-                text.trim_start_matches(HEXADECIMAL_PREFIX),
-                HEXADECIMAL_GROUP_LENGTH,
+            &format_digits(
+                &format_hex_digits(&text[2..], config),
+                config.hexadecimal_group_length,
+                0,
+                config,
+            ),
+        ),
+        _ => (
+            "",
+            &format_digits(
+                text,
+                config.decimal_group_length,
+                config.number_separator_threshold,
+                config,
             ),
         ),
-        (false, false) if text.len() > MIN_LENGTH => {
-            ("", &format_with_underscores(text, DEFAULT_GROUP_LENGTH))
-        }
-        _ => ("", text),
     };
 
-    let mut output = String::new();
-
     output.push_str(prefix);
     output.push_str(text);
+}
+
+fn format_hex_digits(digits: &str, config: &Config) -> String {
+    match config.hex_digit_case {
+        HexDigitCase::Lower => digits.to_lowercase(),
+        HexDigitCase::Upper => digits.to_uppercase(),
+        HexDigitCase::Preserve => digits.to_string(),
+    }
+}
+
+pub fn format_digits(
+    digits: &str,
+    group_length: usize,
+    threshold: usize,
+    config: &Config,
+) -> String {
+    let stripped_digits = digits.replace(SEPARATOR, "");
 
-    output
+    match config.number_separators {
+        NumberSeparators::Preserve => digits.to_string(),
+        NumberSeparators::Strip => stripped_digits,
+        NumberSeparators::Add if stripped_digits.len() > threshold => {
+            format_with_underscores(&stripped_digits, group_length)
+        }
+        NumberSeparators::Add => stripped_digits,
+    }
 }
 
 fn format_with_underscores(s: &str, group_length: usize) -> String {
     let len = s.len();
-    if len <= group_length {
+    if group_length == 0 || len <= group_length {
         return s.to_string();
     }
 
@@ -67,7 +92,7 @@ fn format_with_underscores(s: &str, group_length: usize) -> String {
 
 #[cfg(test)]
 mod tests {
-    use crate::format_code;
+    use crate::{Config, HexDigitCase, NumberSeparators, format_code, format_code_with_config};
     use rstest::*;
 
     #[rstest]
@@ -90,4 +115,73 @@ mod tests {
 
         assert_eq!(formatted, expected_output);
     }
+
+    #[rstest]
+    #[case("var a = 1_000_000", "var a = 1_000_000\n")]
+    #[case("var b = 10_00_000", "var b = 1_000_000\n")]
+    #[case("var c = 0755", "var c = 0755\n")]
+    #[case("var d = 00001234567", "var d = 00_001_234_567\n")]
+    #[case("var e = 0xFF00FF", "var e = 0xff_00ff\n")]
+    #[case("var f = 0XFF", "var f = 0xff\n")]
+    #[case("var g = 0b1_1_0_1_0_0", "var g = 0b11_0100\n")]
+    fn keep_numeric_value(#[case] source_input: &str, #[case] expected_output: &str) {
+        let formatted = format_code(source_input).unwrap();
+
+        assert_eq!(formatted, expected_output);
+    }
+
+    #[rstest]
+    #[case("var a = 1234", "var a = 1_234\n")]
+    #[case("var b = 123", "var b = 123\n")]
+    #[case("var c = 0xFF00FF", "var c = 0xFF_00_FF\n")]
+    #[case("var d = 0b110100", "var d = 0b110_100\n")]
+    fn use_configured_groups(#[case] source_input: &str, #[case] expected_output: &str) {
+        let config = Config {
+            number_separator_threshold: 3,
+            decimal_group_length: 3,
+            binary_group_length: 3,
+            hexadecimal_group_length: 2,
+            hex_digit_case: HexDigitCase::Upper,
+            ..Default::default()
+        };
+
+        let formatted = format_code_with_config(source_input, &config).unwrap();
+
+        assert_eq!(formatted, expected_output);
+    }
+
+    #[rstest]
+    #[case("var a = 1_234_567", "var a = 1234567\n")]
+    #[case("var b = 0xFF_00FF", "var b = 0xff00ff\n")]
+    #[case("var c = 0b11_0100", "var c = 0b110100\n")]
+    #[case("var d = 1_234.567_8", "var d = 1234.5678\n")]
+    fn strip_separators_when_configured(#[case] source_input: &str, #[case] expected_output: &str) {
+        let config = Config {
+            number_separators: NumberSeparators::Strip,
+            ..Default::default()
+        };
+
+        let formatted = format_code_with_config(source_input, &config).unwrap();
+
+        assert_eq!(formatted, expected_output);
+    }
+
+    #[rstest]
+    #[case("var a = 12_34567", "var a = 12_34567\n")]
+    #[case("var b = 0xFF_00FF", "var b = 0xFF_00FF\n")]
+    #[case("var c = 1234567", "var c = 1234567\n")]
+    fn preserve_separators_and_case_when_configured(
+        #[case] source_input: &str,
+        #[case] expected_output: &str,
+    ) {
+        let config = Config {
+            number_separators: NumberSeparators::Preserve,
+            hex_digit_case: HexDigitCase::Preserve,
+            ..Default::default()
+        };
+
+        let formatted = format_code_with_config(source_input, &config).unwrap();
+
+        assert_eq!(formatted, expected_output);
+    }
 }
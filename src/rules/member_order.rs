@@ -0,0 +1,234 @@
+use crate::node::{VIRTUAL_METHODS, get_node_text};
+use tree_sitter::Node;
+
+const CLASS_ANNOTATIONS: [&str; 3] = ["tool", "icon", "static_unload"];
+const PRIVATE_PREFIX: &str = "_";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MemberRank {
+    Header,
+    ClassAnnotation,
+    ClassName,
+    Extends,
+    Signal,
+    Enum,
+    Constant,
+    ExportVariable,
+    PublicVariable,
+    PrivateVariable,
+    OnreadyVariable,
+    Init,
+    VirtualMethod,
+    PublicMethod,
+    PrivateMethod,
+    InnerClass,
+}
+
+impl MemberRank {
+    pub fn is_class_header(self) -> bool {
+        self <= MemberRank::Extends
+    }
+
+    pub fn is_definition(self) -> bool {
+        self >= MemberRank::Init
+    }
+}
+
+pub fn get_rank(node: Node, source: &str) -> Option<MemberRank> {
+    let name = node
+        .child_by_field_name("name")
+        .map(|n| get_node_text(n, source))
+        .unwrap_or_default();
+
+    let rank = match node.kind() {
+        "annotation" if CLASS_ANNOTATIONS.contains(&get_annotation_name(node, source)) => {
+            MemberRank::ClassAnnotation
+        }
+        "tool_statement" => MemberRank::ClassAnnotation,
+        "class_name_statement" => MemberRank::ClassName,
+        "extends_statement" => MemberRank::Extends,
+        "signal_statement" => MemberRank::Signal,
+        "enum_definition" => MemberRank::Enum,
+        "const_statement" => MemberRank::Constant,
+        "export_variable_statement" => MemberRank::ExportVariable,
+        "onready_variable_statement" => MemberRank::OnreadyVariable,
+        "variable_statement" => get_variable_rank(node, source, name),
+        "constructor_definition" => MemberRank::Init,
+        "function_definition" if VIRTUAL_METHODS.contains(&name) => MemberRank::VirtualMethod,
+        "function_definition" if name.starts_with(PRIVATE_PREFIX) => MemberRank::PrivateMethod,
+        "function_definition" => MemberRank::PublicMethod,
+        "class_definition" => MemberRank::InnerClass,
+        _ => return None,
+    };
+
+    Some(rank)
+}
+
+fn get_variable_rank(node: Node, source: &str, name: &str) -> MemberRank {
+    let mut annotations: Vec<&str> = Vec::new();
+
+    let mut prev = node.prev_sibling();
+    while let Some(annotation) = prev.filter(|n| n.kind() == "annotation") {
+        annotations.push(get_annotation_name(annotation, source));
+        prev = annotation.prev_sibling();
+    }
+    for child in node.children(&mut node.walk()) {
+        if child.kind() == "annotations" {
+            for annotation in child.named_children(&mut child.walk()) {
+                annotations.push(get_annotation_name(annotation, source));
+            }
+        }
+    }
+
+    if annotations.contains(&"onready") {
+        MemberRank::OnreadyVariable
+    } else if annotations.iter().any(|a| a.starts_with("export")) {
+        MemberRank::ExportVariable
+    } else if name.starts_with(PRIVATE_PREFIX) {
+        MemberRank::PrivateVariable
+    } else {
+        MemberRank::PublicVariable
+    }
+}
+
+fn get_annotation_name<'a>(node: Node<'a>, source: &'a str) -> &'a str {
+    node.named_child(0)
+        .filter(|n| n.kind() == "identifier")
+        .map(|n| get_node_text(n, source))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Config, format_code_with_config};
+    use rstest::*;
+
+    #[rstest]
+    #[case("var a = 1\nsignal s", "signal s\n\nvar a = 1\n")]
+    #[case(
+        "extends Node\nclass_name Foo\n@tool",
+        "@tool\nclass_name Foo\nextends Node\n"
+    )]
+    #[case(
+        "var _a = 1\nvar b = 2\nconst C = 3",
+        "const C = 3\n\nvar b = 2\n\nvar _a = 1\n"
+    )]
+    #[case(
+        "@onready var a = $A\n@export var b = 1\nvar c = 2",
+        "@export var b = 1\nvar c = 2\n\n@onready var a = $A\n"
+    )]
+    #[case(
+        "func _f():\n\tpass\n\n\nfunc g():\n\tpass\n\n\nfunc _ready():\n\tpass\n\n\nfunc _init():\n\tpass",
+        "func _init():\n\tpass\n\n\nfunc _ready():\n\tpass\n\n\nfunc g():\n\tpass\n\n\nfunc _f():\n\tpass\n"
+    )]
+    #[case("func f():\n\tpass\nvar a = 1", "var a = 1\n\n\nfunc f():\n\tpass\n")]
+    fn reorder_members_to_style_guide_order(
+        #[case] source_input: &str,
+        #[case] expected_output: &str,
+    ) {
+        let config = Config {
+            reorder_members: true,
+            ..Default::default()
+        };
+
+        let formatted = format_code_with_config(source_input, &config).unwrap();
+
+        assert_eq!(formatted, expected_output);
+    }
+
+    #[rstest]
+    #[case("var a = 1\n# about s\nsignal s", "# about s\nsignal s\n\nvar a = 1\n")]
+    #[case(
+        "var a = 1  # x\n## about s\nsignal s",
+        "## about s\nsignal s\n\nvar a = 1  # x\n"
+    )]
+    #[case(
+        "extends Node\n## Class doc.\n\nvar a = 1\nsignal s",
+        "extends Node\n## Class doc.\n\nsignal s\n\nvar a = 1\n"
+    )]
+    #[case(
+        "# header\n\nvar a = 1\nsignal s",
+        "# header\n\nsignal s\n\nvar a = 1\n"
+    )]
+    fn move_attached_comments_with_members(
+        #[case] source_input: &str,
+        #[case] expected_output: &str,
+    ) {
+        let config = Config {
+            reorder_members: true,
+            ..Default::default()
+        };
+
+        let formatted = format_code_with_config(source_input, &config).unwrap();
+
+        assert_eq!(formatted, expected_output);
+    }
+
+    #[rstest]
+    #[case("var c = 1\nvar a = 2\nvar b = 3", "var c = 1\nvar a = 2\nvar b = 3\n")]
+    #[case(
+        "var c = 1\nsignal z\nvar a = 2\nsignal y",
+        "signal z\nsignal y\n\nvar c = 1\nvar a = 2\n"
+    )]
+    #[case(
+        "class A:\n\tfunc f():\n\t\tpass\n\n\tvar b = 1",
+        "class A:\n\tvar b = 1\n\n\tfunc f():\n\t\tpass\n"
+    )]
+    #[case(
+        "func f():\n\tvar b = 1\n\tconst C = 2",
+        "func f():\n\tvar b = 1\n\tconst C = 2\n"
+    )]
+    fn keep_relative_order_within_groups(
+        #[case] source_input: &str,
+        #[case] expected_output: &str,
+    ) {
+        let config = Config {
+            reorder_members: true,
+            ..Default::default()
+        };
+
+        let formatted = format_code_with_config(source_input, &config).unwrap();
+
+        assert_eq!(formatted, expected_output);
+    }
+
+    #[rstest]
+    fn keep_sample_styleguide_order() {
+        let expected = std::fs::read_to_string("samples/styleguide.gd").unwrap();
+        let config = Config {
+            reorder_members: true,
+            ..Default::default()
+        };
+
+        let formatted = format_code_with_config(&expected, &config).unwrap();
+
+        assert_eq!(formatted, expected);
+    }
+
+    #[rstest]
+    #[case(
+        "extends Node\n\n#region Vars\nvar b = 1\nconst A = 1\n#endregion\n\nsignal s",
+        "extends Node\n\n#region Vars\nconst A = 1\n\nvar b = 1\n#endregion\n\nsignal s\n"
+    )]
+    #[case(
+        "#region A\nvar a = 1\n#endregion\n#region B\nsignal s\n#endregion",
+        "#region A\nvar a = 1\n#endregion\n#region B\nsignal s\n#endregion\n"
+    )]
+    #[case(
+        "class A:\n\t#region R\n\tfunc f():\n\t\tpass\n\n\tvar b = 1\n\t#endregion",
+        "class A:\n\t#region R\n\tvar b = 1\n\n\tfunc f():\n\t\tpass\n\t#endregion\n"
+    )]
+    fn keep_members_inside_their_regions(
+        #[case] source_input: &str,
+        #[case] expected_output: &str,
+    ) {
+        let config = Config {
+            reorder_members: true,
+            ..Default::default()
+        };
+
+        let formatted = format_code_with_config(source_input, &config).unwrap();
+
+        assert_eq!(formatted, expected_output);
+    }
+}
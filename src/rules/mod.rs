@@ -3,76 +3,108 @@ mod array;
 mod binary_operator;
 mod body;
 mod class_definition;
+mod collection;
+mod comment;
+mod control_flow;
 mod dictionary;
 mod enum_definition;
 mod float;
 mod function_definition;
 mod integer;
+mod member_order;
 mod parameters;
 mod parenthesized_expression;
 mod setget;
+mod signal_statement;
 mod source;
 mod string;
+mod type_hint;
 mod unary_operator;
 mod variable_statement;
 
+use crate::config::Config;
 use crate::node::{get_gap_lines, get_node_text};
 use crate::text::indent_by;
 use tree_sitter::Node;
 
-pub fn apply(node: Node, source: &str, indent_level: usize) -> String {
+pub fn write(node: Node, source: &str, config: &Config, indent_level: usize, output: &mut String) {
     match node.kind() {
         // call inner nodes
-        "source" => source::apply(node, source, indent_level),
-        "body" => body::apply(node, source, indent_level),
+        "source" => source::write(node, source, config, indent_level, output),
+        "body" => body::write(node, source, config, indent_level, output),
 
         // with trailing line
-        "variable_statement" => variable_statement::apply(node, source, indent_level),
+        "variable_statement" | "const_statement" => {
+            variable_statement::write(node, source, config, indent_level, output)
+        }
         "function_definition" | "constructor_definition" => {
-            function_definition::apply(node, source, indent_level)
+            function_definition::write(node, source, config, indent_level, output)
+        }
+        "class_definition" => class_definition::write(node, source, config, indent_level, output),
+        "enum_definition" => enum_definition::write(node, source, config, indent_level, output),
+        "setget" => setget::write(node, source, config, indent_level, output),
+        "signal_statement" => signal_statement::write(node, source, config, indent_level, output),
+        "if_statement" | "for_statement" | "while_statement" | "match_statement" => {
+            control_flow::write(node, source, config, indent_level, output)
+        }
+        "comment" => comment::write(node, source, config, indent_level, output),
+        "annotation" if is_statement(node) => {
+            annotations::write(node, source, config, indent_level, output)
         }
-        "class_definition" => class_definition::apply(node, source, indent_level),
-        "enum_definition" => enum_definition::apply(node, source, indent_level),
-        "setget" => setget::apply(node, source, indent_level),
 
         "class_name_statement"
         | "extends_statement"
-        | "comment"
-        | "signal_statement"
         | "expression_statement"
         | "pass_statement"
         | "return_statement"
-        | "if_statement"
-        | "for_statement"
-        | "while_statement" => apply_fallback_rules(node, source, indent_level),
+        | "break_statement"
+        | "continue_statement"
+        | "breakpoint_statement"
+        | "tool_statement"
+        | "export_variable_statement"
+        | "onready_variable_statement" => {
+            write_fallback_rules(node, source, config, indent_level, output)
+        }
 
         // without leading/trailing whitespace
-        "annotations" => annotations::apply(node, source, indent_level),
-        "parameters" => parameters::apply(node, source, indent_level),
-        "parenthesized_expression" => parenthesized_expression::apply(node, source, indent_level),
-        "unary_operator" => unary_operator::apply(node, source, indent_level),
-        "binary_operator" => binary_operator::apply(node, source, indent_level),
-        "integer" => integer::apply(node, source, indent_level),
-        "float" => float::apply(node, source, indent_level),
-        "string" => string::apply(node, source, indent_level),
-        "array" => array::apply(node, source, indent_level),
-        "dictionary" => dictionary::apply(node, source, indent_level),
+        "annotations" => annotations::write(node, source, config, indent_level, output),
+        "parameters" => parameters::write(node, source, config, indent_level, output),
+        "parenthesized_expression" => {
+            parenthesized_expression::write(node, source, config, indent_level, output)
+        }
+        "unary_operator" => unary_operator::write(node, source, config, indent_level, output),
+        "binary_operator" => binary_operator::write(node, source, config, indent_level, output),
+        "integer" => integer::write(node, source, config, indent_level, output),
+        "float" => float::write(node, source, config, indent_level, output),
+        "string" | "string_name" | "node_path" => {
+            string::write(node, source, config, indent_level, output)
+        }
+        "array" => array::write(node, source, config, indent_level, output),
+        "dictionary" => dictionary::write(node, source, config, indent_level, output),
+        "type" => type_hint::write(node, source, config, indent_level, output),
 
-        _ => get_node_text(node, source).to_string(),
+        _ => output.push_str(get_node_text(node, source)),
     }
 }
 
-fn apply_fallback_rules(node: Node, source: &str, indent_level: usize) -> String {
+fn write_fallback_rules(
+    node: Node,
+    source: &str,
+    _config: &Config,
+    indent_level: usize,
+    output: &mut String,
+) {
     let text = get_node_text(node, source);
     let gap_lines = get_gap_lines(node, source);
-    let mut output = String::new();
 
     output.push_str(&gap_lines);
-    indent_by(&mut output, indent_level);
+    indent_by(output, indent_level);
     output.push_str(text.trim());
     output.push('\n');
+}
 
-    output
+fn is_statement(node: Node) -> bool {
+    matches!(node.parent().map(|n| n.kind()), Some("source" | "body"))
 }
 
 #[cfg(test)]
@@ -94,6 +126,7 @@ mod tests {
     #[rstest]
     #[case("print(42)", "print(42)\n")]
     #[case("print( 42 )", "print( 42 )\n")]
+    #[case("print(a, # c\n\tb)", "print(a, # c\n\tb)\n")]
     fn keep_text(#[case] source_input: &str, #[case] expected_output: &str) {
         let formatted = format_code(source_input).unwrap();
 
@@ -1,46 +1,78 @@
+use crate::config::Config;
 use tree_sitter::Node;
 
-pub fn apply(node: Node, source: &str, indent_level: usize) -> String {
+pub fn write(node: Node, source: &str, config: &Config, indent_level: usize, output: &mut String) {
     match node.kind() {
-        "parameters" => apply_parameters_rules(node, source, indent_level),
-        "default_parameter" => apply_default_parameter_rules(node, source, indent_level),
-        _ => super::apply(node, source, indent_level),
+        "parameters" => write_parameters_rules(node, source, config, indent_level, output),
+        "default_parameter" => {
+            write_default_parameter_rules(node, source, config, indent_level, output)
+        }
+        "typed_parameter" | "typed_default_parameter" => {
+            write_typed_parameter_rules(node, source, config, indent_level, output)
+        }
+        _ => super::write(node, source, config, indent_level, output),
     }
 }
 
-fn apply_parameters_rules(node: Node, source: &str, indent_level: usize) -> String {
-    let mut output = String::new();
-
+fn write_parameters_rules(
+    node: Node,
+    source: &str,
+    config: &Config,
+    indent_level: usize,
+    output: &mut String,
+) {
     for child in node.children(&mut node.walk()) {
         let prev_kind = child.prev_sibling().map(|ps| ps.kind());
-        let child_apply_fn = || apply(child, source, indent_level);
-        let (text, space): (&str, &str) = match child.kind() {
-            "(" | ")" | "=" | "," => (&child_apply_fn(), ""),
-            "identifier" | "default_parameter" if prev_kind == Some("(") => (&child_apply_fn(), ""),
-            "identifier" => (&child_apply_fn(), " "),
-            _ => (&child_apply_fn(), " "),
-        };
-        output.push_str(space);
-        output.push_str(text);
+        match child.kind() {
+            "(" | ")" | "=" | "," => {}
+            "comment" => {
+                super::comment::write_inline(child, source, config, indent_level, output);
+                continue;
+            }
+            _ if matches!(prev_kind, Some("(" | "comment")) => {}
+            _ => output.push(' '),
+        }
+        write(child, source, config, indent_level, output);
     }
-
-    output
 }
 
-fn apply_default_parameter_rules(node: Node, source: &str, indent_level: usize) -> String {
-    let mut output = String::new();
-
+fn write_default_parameter_rules(
+    node: Node,
+    source: &str,
+    config: &Config,
+    indent_level: usize,
+    output: &mut String,
+) {
     for child in node.children(&mut node.walk()) {
-        let child_apply_fn = || apply(child, source, indent_level);
-        let (text, space): (&str, &str) = match child.kind() {
-            "identifier" => (&child_apply_fn(), ""),
-            _ => (&child_apply_fn(), ""),
-        };
-        output.push_str(space);
-        output.push_str(text);
+        if child.kind() == "comment" {
+            super::comment::write_inline(child, source, config, indent_level, output);
+            continue;
+        }
+        write(child, source, config, indent_level, output);
     }
+}
 
-    output
+fn write_typed_parameter_rules(
+    node: Node,
+    source: &str,
+    config: &Config,
+    indent_level: usize,
+    output: &mut String,
+) {
+    for child in node.children(&mut node.walk()) {
+        let prev_kind = child.prev_sibling().map(|ps| ps.kind());
+        match child.kind() {
+            _ if prev_kind.is_none() => {}
+            ":" => {}
+            "comment" => {
+                super::comment::write_inline(child, source, config, indent_level, output);
+                continue;
+            }
+            _ if prev_kind == Some("comment") => {}
+            _ => output.push(' '),
+        }
+        write(child, source, config, indent_level, output);
+    }
 }
 
 #[cfg(test)]
@@ -55,9 +87,30 @@ mod tests {
     #[case("func d(p1,p2 = 42): pass", "func d(p1, p2=42):\n\tpass\n")]
     #[case("func e(p1 = 24,p2): pass", "func e(p1=24, p2):\n\tpass\n")]
     #[case("func f(p1 = 24,p2 = 42): pass", "func f(p1=24, p2=42):\n\tpass\n")]
+    #[case("func g(p1:int,p2 :int): pass", "func g(p1: int, p2: int):\n\tpass\n")]
+    #[case("func h(p1:int=1,p2): pass", "func h(p1: int = 1, p2):\n\tpass\n")]
+    #[case("func i( p1 : Array[int] ): pass", "func i(p1: Array[int]):\n\tpass\n")]
     fn enforce_spacing_rules(#[case] source_input: &str, #[case] expected_output: &str) {
         let formatted = format_code(source_input).unwrap();
 
         assert_eq!(formatted, expected_output);
     }
+
+    #[rstest]
+    #[case(
+        "func f(a, # first\n\tb):\n\tpass",
+        "func f(a,  # first\n\tb):\n\tpass\n"
+    )]
+    #[case("func f(a, b # last\n):\n\tpass", "func f(a, b  # last\n):\n\tpass\n")]
+    #[case("func f(a: # c\n\tint):\n\tpass", "func f(a:  # c\n\tint):\n\tpass\n")]
+    #[case("func f(a = # c\n\t1):\n\tpass", "func f(a=  # c\n\t1):\n\tpass\n")]
+    #[case(
+        "class A:\n\tfunc f(a, # c\n\t\tb):\n\t\tpass",
+        "class A:\n\tfunc f(a,  # c\n\t\tb):\n\t\tpass\n"
+    )]
+    fn break_line_after_comments(#[case] source_input: &str, #[case] expected_output: &str) {
+        let formatted = format_code(source_input).unwrap();
+
+        assert_eq!(formatted, expected_output);
+    }
 }
@@ -1,20 +1,22 @@
+use crate::config::Config;
 use tree_sitter::Node;
 
-pub fn apply(node: Node, source: &str, indent_level: usize) -> String {
-    let mut output = String::new();
+pub fn write(node: Node, source: &str, config: &Config, indent_level: usize, output: &mut String) {
+    let has_comment = contains_comment(node);
 
     for child in node.children(&mut node.walk()) {
-        let child_apply_fn = || super::apply(child, source, indent_level);
-        let (text, space): (&str, &str) = match child.kind() {
-            "(" => ("", ""),
-            ")" => ("", ""),
-            _ => (&child_apply_fn(), ""),
-        };
-        output.push_str(space);
-        output.push_str(text);
+        match child.kind() {
+            "(" | ")" if has_comment => output.push_str(child.kind()),
+            "(" | ")" => {}
+            "comment" => super::comment::write_inline(child, source, config, indent_level, output),
+            _ => super::write(child, source, config, indent_level, output),
+        }
     }
+}
 
-    output
+/// Comments need the parentheses to stay valid once they break the line.
+fn contains_comment(node: Node) -> bool {
+    node.kind() == "comment" || node.children(&mut node.walk()).any(contains_comment)
 }
 
 #[cfg(test)]
@@ -30,4 +32,13 @@ mod tests {
 
         assert_eq!(formatted, expected_output);
     }
+
+    #[rstest]
+    #[case("var a = (1 # c\n)", "var a = (1  # c\n)\n")]
+    #[case("var b = (not # c\n\ta)", "var b = (not  # c\n\ta)\n")]
+    fn break_line_after_comments(#[case] source_input: &str, #[case] expected_output: &str) {
+        let formatted = format_code(source_input).unwrap();
+
+        assert_eq!(formatted, expected_output);
+    }
 }
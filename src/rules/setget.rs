@@ -1,68 +1,84 @@
+use crate::config::Config;
+use crate::node::is_trailing_comment;
 use crate::text::indent_by;
 use tree_sitter::Node;
 
-pub fn apply(node: Node, source: &str, indent_level: usize) -> String {
+pub fn write(node: Node, source: &str, config: &Config, indent_level: usize, output: &mut String) {
     match node.kind() {
-        "setget" => apply_setget_rules(node, source, indent_level),
-        "set_body" | "get_body" => apply_setget_body_rules(node, source, indent_level),
-        "body" => super::apply(node, source, indent_level + 1),
-        _ => super::apply(node, source, indent_level),
+        "setget" => write_setget_rules(node, source, config, indent_level + 1, output),
+        "set_body" | "get_body" => {
+            write_setget_body_rules(node, source, config, indent_level, output)
+        }
+        "body" => super::write(node, source, config, indent_level + 1, output),
+        _ => super::write(node, source, config, indent_level, output),
     }
 }
 
-fn apply_setget_rules(node: Node, source: &str, indent_level: usize) -> String {
-    let mut output = String::new();
-
+fn write_setget_rules(
+    node: Node,
+    source: &str,
+    config: &Config,
+    indent_level: usize,
+    output: &mut String,
+) {
     for child in node.children(&mut node.walk()) {
         let prev_kind = child.prev_sibling().map(|ps| ps.kind());
-        let child_apply_fn = || apply(child, source, indent_level);
-        let (text, space): (&str, &str) = match child.kind() {
+        match child.kind() {
             ":" => {
-                output.push_str(&child_apply_fn());
+                write(child, source, config, indent_level, output);
+                output.push('\n');
+                continue;
+            }
+            "comment" if is_trailing_comment(child) => {
+                super::comment::write_trailing(child, source, config, output);
+                output.push('\n');
+                continue;
+            }
+            "comment" => {
+                indent_by(output, indent_level);
+                write(child, source, config, indent_level, output);
                 output.push('\n');
-                ("", "")
+                continue;
             }
-            "get" | "set" => {
-                if prev_kind == Some(":") {
-                    indent_by(&mut output, indent_level);
-                } else {
-                    output.push(' ');
-                }
-                (&child_apply_fn(), "")
+            "get" | "set" if matches!(prev_kind, Some(":" | "comment")) => {
+                indent_by(output, indent_level)
             }
-            "=" | "getter" | "setter" => (&child_apply_fn(), " "),
-            "," => (&child_apply_fn(), ""),
-            "set_body" | "get_body" => (&child_apply_fn(), ""),
-            _ => (&child_apply_fn(), ""),
-        };
-        output.push_str(space);
-        output.push_str(text);
+            "get" | "set" | "=" | "getter" | "setter" => output.push(' '),
+            _ => {}
+        }
+        write(child, source, config, indent_level, output);
     }
-
-    output
 }
 
-fn apply_setget_body_rules(node: Node, source: &str, indent_level: usize) -> String {
-    let mut output = String::new();
-
-    indent_by(&mut output, indent_level);
+fn write_setget_body_rules(
+    node: Node,
+    source: &str,
+    config: &Config,
+    indent_level: usize,
+    output: &mut String,
+) {
+    indent_by(output, indent_level);
 
     for child in node.children(&mut node.walk()) {
-        let child_apply_fn = || apply(child, source, indent_level);
-        let (text, space): (&str, &str) = match child.kind() {
-            "set" | "get" => (&child_apply_fn(), ""),
-            ":" => {
-                output.push_str(&child_apply_fn());
+        match child.kind() {
+            "comment" if is_trailing_comment(child) => {
+                super::comment::write_trailing(child, source, config, output);
                 output.push('\n');
-                ("", "")
+                continue;
             }
-            _ => (&child_apply_fn(), ""),
-        };
-        output.push_str(space);
-        output.push_str(text);
+            "comment" => {
+                indent_by(output, indent_level + 1);
+                write(child, source, config, indent_level, output);
+                output.push('\n');
+                continue;
+            }
+            _ => {}
+        }
+        write(child, source, config, indent_level, output);
+        if child.kind() == ":" {
+            output.push('\n');
+        }
     }
-
-    output
 }
 
 #[cfg(test)]
@@ -90,6 +106,16 @@ mod tests {
         "var d:\n\tset(value):\n\t\td = value\n\tget:\n\t\treturn 42",
         "var d:\n\tset(value):\n\t\td = value\n\tget:\n\t\treturn 42\n"
     )]
+    #[case(
+        "var x = 1:\n\tget: # d\n\t\treturn 1",
+        "var x = 1:\n\tget:  # d\n\t\treturn 1\n"
+    )]
+    #[case("var y = 1: # c\n\tset = f", "var y = 1:  # c\n\tset = f\n")]
+    #[case("var z = 1:\n\t# own\n\tset = f", "var z = 1:\n\t# own\n\tset = f\n")]
+    #[case(
+        "class A:\n\tvar w:\n\t\tset(v): # s\n\t\t\tw = v",
+        "class A:\n\tvar w:\n\t\tset(v):  # s\n\t\t\tw = v\n"
+    )]
     fn enforce_spacing_rules(#[case] source_input: &str, #[case] expected_output: &str) {
         let formatted = format_code(source_input).unwrap();
 
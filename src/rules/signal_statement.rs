@@ -0,0 +1,85 @@
+use crate::config::Config;
+use crate::node::get_gap_lines;
+use crate::text::indent_by;
+use tree_sitter::Node;
+
+pub fn write(node: Node, source: &str, config: &Config, indent_level: usize, output: &mut String) {
+    let gap_lines = get_gap_lines(node, source);
+
+    output.push_str(&gap_lines);
+    indent_by(output, indent_level);
+
+    for child in node.children(&mut node.walk()) {
+        match child.kind() {
+            "signal" => {}
+            "parameters" if is_empty(child) && !config.keep_empty_signal_parentheses => continue,
+            "parameters" => {}
+            _ => output.push(' '),
+        }
+        super::write(child, source, config, indent_level, output);
+    }
+    output.push('\n');
+}
+
+fn is_empty(parameters: Node) -> bool {
+    parameters.named_child_count() == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Config, format_code, format_code_with_config};
+    use rstest::*;
+
+    #[rstest]
+    #[case("signal a", "signal a\n")]
+    #[case("signal  b", "signal b\n")]
+    #[case("signal c()", "signal c\n")]
+    #[case("signal d( )", "signal d\n")]
+    #[case(
+        "signal  state_changed( previous ,new )",
+        "signal state_changed(previous, new)\n"
+    )]
+    #[case(
+        "signal e(x : int,y:Array [ int ])",
+        "signal e(x: int, y: Array[int])\n"
+    )]
+    #[case("signal f\nsignal g", "signal f\nsignal g\n")]
+    #[case("signal f\n\n\nsignal g", "signal f\n\nsignal g\n")]
+    #[case("class A:\n\tsignal  h( )", "class A:\n\tsignal h\n")]
+    #[case(
+        "@warning_ignore( \"unused_signal\" )\nsignal i",
+        "@warning_ignore(\"unused_signal\")\nsignal i\n"
+    )]
+    fn enforce_spacing_rules(#[case] source_input: &str, #[case] expected_output: &str) {
+        let formatted = format_code(source_input).unwrap();
+
+        assert_eq!(formatted, expected_output);
+    }
+
+    #[rstest]
+    #[case("signal a", "signal a\n")]
+    #[case("signal b()", "signal b()\n")]
+    #[case("signal c( )", "signal c()\n")]
+    #[case("signal d(x)", "signal d(x)\n")]
+    fn keep_empty_parentheses_when_configured(
+        #[case] source_input: &str,
+        #[case] expected_output: &str,
+    ) {
+        let config = Config {
+            keep_empty_signal_parentheses: true,
+            ..Default::default()
+        };
+
+        let formatted = format_code_with_config(source_input, &config).unwrap();
+
+        assert_eq!(formatted, expected_output);
+    }
+
+    #[rstest]
+    #[case("signal s(a, # c\n\tb)", "signal s(a,  # c\n\tb)\n")]
+    fn break_line_after_comments(#[case] source_input: &str, #[case] expected_output: &str) {
+        let formatted = format_code(source_input).unwrap();
+
+        assert_eq!(formatted, expected_output);
+    }
+}
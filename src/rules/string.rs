@@ -1,62 +1,151 @@
+use crate::config::{Config, QuoteStyle};
 use crate::node::get_node_text;
 use tree_sitter::Node;
 
-const SINGLE_QUOTE: &str = "\'";
-const ESCAPED_SINGLE_QUOTE: &str = "\\\'";
-const DOUBLE_QUOTE: &str = "\"";
-const ESCAPED_DOUBLE_QUOTE: &str = "\\\"";
+const SINGLE_QUOTE: char = '\'';
+const DOUBLE_QUOTE: char = '"';
+const ESCAPE: char = '\\';
+const TRIPLE_SINGLE_QUOTE: &str = "'''";
+const TRIPLE_DOUBLE_QUOTE: &str = "\"\"\"";
+const RAW_PREFIX: char = 'r';
 
-pub fn apply(node: Node, source: &str, _indent_level: usize) -> String {
+pub fn write(node: Node, source: &str, config: &Config, _indent_level: usize, output: &mut String) {
     let raw_text = get_node_text(node, source);
-    let surround_quote = raw_text.chars().next().unwrap_or('"');
-    let text = raw_text.trim_matches(surround_quote);
-    let has_single_quote = text.contains(SINGLE_QUOTE);
-    let has_double_quote = text.contains(DOUBLE_QUOTE);
-
-    let (surround_quote, text): (&str, &str) = match (
-        surround_quote.to_string().as_ref(),
-        has_single_quote,
-        has_double_quote,
-    ) {
-        (SINGLE_QUOTE, true, _) => (
-            DOUBLE_QUOTE,
-            &text
-                .replace(ESCAPED_SINGLE_QUOTE, SINGLE_QUOTE)
-                .replace(ESCAPED_DOUBLE_QUOTE, DOUBLE_QUOTE)
-                .replace(DOUBLE_QUOTE, ESCAPED_DOUBLE_QUOTE),
-        ),
-        (SINGLE_QUOTE, _, true) => (
-            SINGLE_QUOTE,
-            &text.replace(ESCAPED_DOUBLE_QUOTE, DOUBLE_QUOTE),
-        ),
-        (SINGLE_QUOTE, _, _) => (DOUBLE_QUOTE, text),
-
-        (DOUBLE_QUOTE, true, _) => (
-            DOUBLE_QUOTE,
-            &text
-                .replace(ESCAPED_SINGLE_QUOTE, SINGLE_QUOTE)
-                .replace(ESCAPED_DOUBLE_QUOTE, DOUBLE_QUOTE)
-                .replace(DOUBLE_QUOTE, ESCAPED_DOUBLE_QUOTE),
-        ),
-        (DOUBLE_QUOTE, _, true) => (
-            SINGLE_QUOTE,
-            &text.replace(ESCAPED_DOUBLE_QUOTE, DOUBLE_QUOTE),
-        ),
-        _ => (DOUBLE_QUOTE, text),
+    let quote_start = raw_text.find([DOUBLE_QUOTE, SINGLE_QUOTE]).unwrap_or(0);
+    let (prefix, literal) = raw_text.split_at(quote_start);
+
+    output.push_str(prefix);
+    if literal.starts_with(TRIPLE_SINGLE_QUOTE) || literal.starts_with(TRIPLE_DOUBLE_QUOTE) {
+        output.push_str(&normalize_triple_quotes(literal, config));
+    } else if prefix.contains(RAW_PREFIX) {
+        output.push_str(&normalize_raw_quotes(literal, config));
+    } else {
+        output.push_str(&normalize_quotes(literal, config));
+    }
+}
+
+fn preferred_quotes(config: &Config) -> Option<(char, char)> {
+    match config.quote_style {
+        QuoteStyle::Double => Some((DOUBLE_QUOTE, SINGLE_QUOTE)),
+        QuoteStyle::Single => Some((SINGLE_QUOTE, DOUBLE_QUOTE)),
+        QuoteStyle::Preserve => None,
+    }
+}
+
+fn normalize_triple_quotes(literal: &str, config: &Config) -> String {
+    let Some((quote, _)) = preferred_quotes(config).filter(|_| config.normalize_triple_quotes)
+    else {
+        return literal.to_string();
+    };
+    let delimiter = &literal[..TRIPLE_DOUBLE_QUOTE.len()];
+    let text = literal
+        .strip_prefix(delimiter)
+        .and_then(|text| text.strip_suffix(delimiter));
+
+    match text {
+        Some(text) if !text.contains(quote) => {
+            let delimiter = quote.to_string().repeat(3);
+            format!("{}{}{}", delimiter, text, delimiter)
+        }
+        _ => literal.to_string(),
+    }
+}
+
+fn normalize_raw_quotes(literal: &str, config: &Config) -> String {
+    let Some((quote, _)) = preferred_quotes(config) else {
+        return literal.to_string();
+    };
+    let (_, text) = split_quotes(literal);
+
+    if text.contains(quote) || text.ends_with(ESCAPE) {
+        return literal.to_string();
+    }
+
+    format!("{}{}{}", quote, text, quote)
+}
+
+fn split_quotes(literal: &str) -> (char, &str) {
+    let surround_quote = if literal.starts_with(SINGLE_QUOTE) {
+        SINGLE_QUOTE
+    } else {
+        DOUBLE_QUOTE
+    };
+    let text = literal
+        .strip_prefix(surround_quote)
+        .and_then(|text| text.strip_suffix(surround_quote))
+        .unwrap_or(literal);
+
+    (surround_quote, text)
+}
+
+fn normalize_quotes(literal: &str, config: &Config) -> String {
+    let Some((preferred_quote, alternate_quote)) = preferred_quotes(config) else {
+        return literal.to_string();
+    };
+    let (_, text) = split_quotes(literal);
+    let text = unescape_quotes(text);
+    let count_quotes = |quote: char| text.chars().filter(|c| *c == quote).count();
+
+    let surround_quote = if config.minimize_escapes
+        && count_quotes(preferred_quote) > count_quotes(alternate_quote)
+    {
+        alternate_quote
+    } else {
+        preferred_quote
     };
 
     let mut output = String::new();
 
-    output.push_str(surround_quote);
-    output.push_str(text);
-    output.push_str(surround_quote);
+    output.push(surround_quote);
+    output.push_str(&escape_quotes(&text, surround_quote));
+    output.push(surround_quote);
+
+    output
+}
+
+fn unescape_quotes(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        if c != ESCAPE {
+            output.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some(next @ (SINGLE_QUOTE | DOUBLE_QUOTE)) => output.push(next),
+            next => {
+                output.push(c);
+                output.extend(next);
+            }
+        }
+    }
+
+    output
+}
+
+fn escape_quotes(text: &str, quote: char) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        if c == ESCAPE {
+            output.push(c);
+            output.extend(chars.next());
+            continue;
+        }
+        if c == quote {
+            output.push(ESCAPE);
+        }
+        output.push(c);
+    }
 
     output
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::format_code;
+    use crate::{Config, QuoteStyle, format_code, format_code_with_config};
     use rstest::*;
 
     #[rstest]
@@ -86,4 +175,164 @@ mod tests {
 
         assert_eq!(formatted, expected_output);
     }
+
+    #[rstest]
+    #[case(r#"var a = &"name""#, "var a = &\"name\"\n")]
+    #[case("var b = &'name'", "var b = &\"name\"\n")]
+    #[case(r#"var c = &'say "hi"'"#, "var c = &'say \"hi\"'\n")]
+    #[case(r#"var d = ^"Path/To""#, "var d = ^\"Path/To\"\n")]
+    #[case("var e = ^'Path/To'", "var e = ^\"Path/To\"\n")]
+    #[case(r#"var f = "ends with \"""#, "var f = 'ends with \"'\n")]
+    fn keep_string_prefixes(#[case] source_input: &str, #[case] expected_output: &str) {
+        let formatted = format_code(source_input).unwrap();
+
+        assert_eq!(formatted, expected_output);
+    }
+
+    #[rstest]
+    #[case(r#"var a = r"\d+""#, "var a = r\"\\d+\"\n")]
+    #[case(r"var b = r'\d+'", "var b = r\"\\d+\"\n")]
+    #[case(r#"var c = r'say "hi"'"#, "var c = r'say \"hi\"'\n")]
+    #[case(r#"var d = r"it's""#, "var d = r\"it's\"\n")]
+    #[case(r"var e = r'a\'b'", "var e = r\"a\\'b\"\n")]
+    #[case(r#"var f = r"a\"b""#, "var f = r\"a\\\"b\"\n")]
+    fn never_rewrite_raw_strings(#[case] source_input: &str, #[case] expected_output: &str) {
+        let formatted = format_code(source_input).unwrap();
+
+        assert_eq!(formatted, expected_output);
+    }
+
+    #[rstest]
+    #[case(r#"var a = """hello""""#, "var a = \"\"\"hello\"\"\"\n")]
+    #[case("var b = '''hello'''", "var b = \"\"\"hello\"\"\"\n")]
+    #[case(
+        "var c = \"\"\"\n\tline 1  \n  line 2\\\"\n\"\"\"",
+        "var c = \"\"\"\n\tline 1  \n  line 2\\\"\n\"\"\"\n"
+    )]
+    #[case("var d = '''say \"hi\"'''", "var d = '''say \"hi\"'''\n")]
+    #[case("var e = '''it\\'s\n  '''", "var e = \"\"\"it\\'s\n  \"\"\"\n")]
+    #[case("var f = r'''\\d'''", "var f = r\"\"\"\\d\"\"\"\n")]
+    #[case(
+        "func f():\n\tprint(\"\"\"\n  a\n\t\"\"\")",
+        "func f():\n\tprint(\"\"\"\n  a\n\t\"\"\")\n"
+    )]
+    fn preserve_triple_quoted_content(#[case] source_input: &str, #[case] expected_output: &str) {
+        let formatted = format_code(source_input).unwrap();
+
+        assert_eq!(formatted, expected_output);
+    }
+
+    #[rstest]
+    #[case("var a = '''hello'''", "var a = '''hello'''\n")]
+    #[case(r#"var b = """hello""""#, "var b = \"\"\"hello\"\"\"\n")]
+    fn keep_triple_quotes_when_configured(
+        #[case] source_input: &str,
+        #[case] expected_output: &str,
+    ) {
+        let config = Config {
+            normalize_triple_quotes: false,
+            ..Default::default()
+        };
+
+        let formatted = format_code_with_config(source_input, &config).unwrap();
+
+        assert_eq!(formatted, expected_output);
+    }
+
+    #[rstest]
+    #[case(r#"var a = "hello world""#, r#"var a = 'hello world'"#)]
+    #[case(r#"var b = 'hello world'"#, r#"var b = 'hello world'"#)]
+    #[case(r#"var c = 'hello" "world'"#, r#"var c = 'hello" "world'"#)]
+    #[case(r#"var d = 'hello\' \'world'"#, r#"var d = "hello' 'world""#)]
+    #[case(r#"var e = "hello\" \"world""#, r#"var e = 'hello" "world'"#)]
+    #[case(r#"var f = 'hello\"\' \'\"world'"#, r#"var f = 'hello"\' \'"world'"#)]
+    #[case(r#"var g = "hello\"\' \'\"world""#, r#"var g = 'hello"\' \'"world'"#)]
+    #[case(
+        r#"var h = 'hello\"\'\"\' \'\"\'\"world'"#,
+        r#"var h = 'hello"\'"\' \'"\'"world'"#
+    )]
+    #[case(
+        r#"var i = "hello\"\'\"\' \'\"\'\"world""#,
+        r#"var i = 'hello"\'"\' \'"\'"world'"#
+    )]
+    fn prefer_single_quotes_when_configured(
+        #[case] source_input: &str,
+        #[case] expected_output: &str,
+    ) {
+        let config = Config {
+            quote_style: QuoteStyle::Single,
+            ..Default::default()
+        };
+
+        let formatted = format_code_with_config(source_input, &config).unwrap();
+
+        assert_eq!(formatted, format!("{}\n", expected_output));
+    }
+
+    #[rstest]
+    #[case(r#"var a = "hello world""#, r#"var a = "hello world""#)]
+    #[case(r#"var b = 'hello world'"#, r#"var b = 'hello world'"#)]
+    #[case(r#"var c = 'hello" "world'"#, r#"var c = 'hello" "world'"#)]
+    #[case(r#"var d = 'hello\' \'world'"#, r#"var d = 'hello\' \'world'"#)]
+    #[case(r#"var e = "hello\" \"world""#, r#"var e = "hello\" \"world""#)]
+    #[case(r#"var f = 'hello\"\' \'\"world'"#, r#"var f = 'hello\"\' \'\"world'"#)]
+    #[case(r#"var g = "hello\"\' \'\"world""#, r#"var g = "hello\"\' \'\"world""#)]
+    #[case(
+        r#"var h = 'hello\"\'\"\' \'\"\'\"world'"#,
+        r#"var h = 'hello\"\'\"\' \'\"\'\"world'"#
+    )]
+    #[case(
+        r#"var i = "hello\"\'\"\' \'\"\'\"world""#,
+        r#"var i = "hello\"\'\"\' \'\"\'\"world""#
+    )]
+    fn preserve_quotes_when_configured(#[case] source_input: &str, #[case] expected_output: &str) {
+        let config = Config {
+            quote_style: QuoteStyle::Preserve,
+            ..Default::default()
+        };
+
+        let formatted = format_code_with_config(source_input, &config).unwrap();
+
+        assert_eq!(formatted, format!("{}\n", expected_output));
+    }
+
+    #[rstest]
+    #[case(r#"var a = "hello world""#, r#"var a = "hello world""#)]
+    #[case(r#"var b = 'hello world'"#, r#"var b = "hello world""#)]
+    #[case(r#"var c = 'hello" "world'"#, r#"var c = "hello\" \"world""#)]
+    #[case(r#"var d = 'hello\' \'world'"#, r#"var d = "hello' 'world""#)]
+    #[case(r#"var e = "hello\" \"world""#, r#"var e = "hello\" \"world""#)]
+    #[case(r#"var f = 'hello\"\' \'\"world'"#, r#"var f = "hello\"' '\"world""#)]
+    #[case(r#"var g = "hello\"\' \'\"world""#, r#"var g = "hello\"' '\"world""#)]
+    #[case(
+        r#"var h = 'hello\"\'\"\' \'\"\'\"world'"#,
+        r#"var h = "hello\"'\"' '\"'\"world""#
+    )]
+    #[case(
+        r#"var i = "hello\"\'\"\' \'\"\'\"world""#,
+        r#"var i = "hello\"'\"' '\"'\"world""#
+    )]
+    fn always_use_preferred_quotes_without_minimal_escapes(
+        #[case] source_input: &str,
+        #[case] expected_output: &str,
+    ) {
+        let config = Config {
+            minimize_escapes: false,
+            ..Default::default()
+        };
+
+        let formatted = format_code_with_config(source_input, &config).unwrap();
+
+        assert_eq!(formatted, format!("{}\n", expected_output));
+    }
+
+    #[rstest]
+    #[case(r#"var a = "a\\" + 'b'"#, r#"var a = "a\\" + "b""#)]
+    #[case(r#"var b = 'a\\'"#, r#"var b = "a\\""#)]
+    #[case(r#"var c = 'a\n\'b\''"#, r#"var c = "a\n'b'""#)]
+    fn keep_other_escape_sequences(#[case] source_input: &str, #[case] expected_output: &str) {
+        let formatted = format_code(source_input).unwrap();
+
+        assert_eq!(formatted, format!("{}\n", expected_output));
+    }
 }
@@ -0,0 +1,57 @@
+use crate::config::Config;
+use crate::node::get_node_text;
+use tree_sitter::Node;
+
+pub fn write(
+    node: Node,
+    source: &str,
+    _config: &Config,
+    _indent_level: usize,
+    output: &mut String,
+) {
+    write_type_rules(node, source, output);
+}
+
+fn write_type_rules(node: Node, source: &str, output: &mut String) {
+    if node.child_count() == 0 {
+        output.push_str(get_node_text(node, source));
+        return;
+    }
+
+    for child in node.children(&mut node.walk()) {
+        let prev_kind = child.prev_sibling().map(|ps| ps.kind());
+        if prev_kind == Some(",") {
+            output.push(' ');
+        }
+        write_type_rules(child, source, output);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::format_code;
+    use rstest::*;
+
+    #[rstest]
+    #[case("var a :int=1", "var a: int = 1\n")]
+    #[case("var b:=2", "var b := 2\n")]
+    #[case("var c : Array [ int ] = []", "var c: Array[int] = []\n")]
+    #[case("var d: Node . Foo", "var d: Node.Foo\n")]
+    #[case(
+        "func f(x : int = 1)->void:\n\tpass",
+        "func f(x: int = 1) -> void:\n\tpass\n"
+    )]
+    #[case(
+        "func g() -> Array [ String ] :\n\tpass",
+        "func g() -> Array[String]:\n\tpass\n"
+    )]
+    #[case(
+        "func _init(a : int)->void:\n\tpass",
+        "func _init(a: int) -> void:\n\tpass\n"
+    )]
+    fn normalize_type_hints(#[case] source_input: &str, #[case] expected_output: &str) {
+        let formatted = format_code(source_input).unwrap();
+
+        assert_eq!(formatted, expected_output);
+    }
+}
@@ -1,20 +1,15 @@
+use crate::config::Config;
 use tree_sitter::Node;
 
-pub fn apply(node: Node, source: &str, indent_level: usize) -> String {
-    let mut output = String::new();
-
+pub fn write(node: Node, source: &str, config: &Config, indent_level: usize, output: &mut String) {
     for child in node.children(&mut node.walk()) {
-        let child_apply_fn = || super::apply(child, source, indent_level);
-        let (text, space): (&str, &str) = match child.kind() {
-            "!" | "not" => ("not ", ""),
-            "+" => ("", ""),
-            _ => (&child_apply_fn(), ""),
-        };
-        output.push_str(space);
-        output.push_str(text);
+        match child.kind() {
+            "!" | "not" => output.push_str("not "),
+            "+" => {}
+            "comment" => super::comment::write_inline(child, source, config, indent_level, output),
+            _ => super::write(child, source, config, indent_level, output),
+        }
     }
-
-    output
 }
 
 #[cfg(test)]
@@ -24,6 +19,11 @@ mod tests {
 
     #[rstest]
     #[case("var a=-1", "var a = -1\n")]
+    #[case("var b = - 1.5", "var b = -1.5\n")]
+    #[case("var c = -.5", "var c = -0.5\n")]
+    #[case("var d = -1E5", "var d = -1e5\n")]
+    #[case("var e = - INF", "var e = -INF\n")]
+    #[case("var f = -NAN", "var f = -NAN\n")]
     fn enforce_spacing_rules(#[case] source_input: &str, #[case] expected_output: &str) {
         let formatted = format_code(source_input).unwrap();
 
@@ -32,6 +32,8 @@ mod tests {
 
     #[rstest]
     #[case("var a=+1", "var a = 1\n")]
+    #[case("var b = +1.5", "var b = 1.5\n")]
+    #[case("var c = +INF", "var c = INF\n")]
     fn remove_useless_operator(#[case] source_input: &str, #[case] expected_output: &str) {
         let formatted = format_code(source_input).unwrap();
 
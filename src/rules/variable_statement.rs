@@ -1,27 +1,29 @@
+use crate::config::Config;
 use crate::node::get_gap_lines;
 use crate::text::indent_by;
 use tree_sitter::Node;
 
-pub fn apply(node: Node, source: &str, indent_level: usize) -> String {
+pub fn write(node: Node, source: &str, config: &Config, indent_level: usize, output: &mut String) {
     let gap_lines = get_gap_lines(node, source);
-    let mut output = String::new();
 
     output.push_str(&gap_lines);
-    indent_by(&mut output, indent_level);
+    indent_by(output, indent_level);
 
     for child in node.children(&mut node.walk()) {
-        let child_apply_fn = || super::apply(child, source, indent_level + 1);
-        let (text, space): (&str, &str) = match child.kind() {
-            _ if child.prev_sibling().is_none() => (&child_apply_fn(), ""),
-            ":" | "setget" => (&child_apply_fn(), ""),
-            _ => (&child_apply_fn(), " "),
-        };
-        output.push_str(space);
-        output.push_str(text);
+        let prev_kind = child.prev_sibling().map(|ps| ps.kind());
+        match child.kind() {
+            _ if prev_kind.is_none() => {}
+            ":" | "setget" => {}
+            "comment" => {
+                super::comment::write_inline(child, source, config, indent_level, output);
+                continue;
+            }
+            _ if prev_kind == Some("comment") => {}
+            _ => output.push(' '),
+        }
+        super::write(child, source, config, indent_level, output);
     }
     output.push('\n');
-
-    output
 }
 
 #[cfg(test)]
@@ -37,9 +39,18 @@ mod tests {
     #[case("var a = 0\nvar b = 0", "var a = 0\nvar b = 0\n")]
     #[case("var b = 0\n\nvar b = 0", "var b = 0\n\nvar b = 0\n")]
     #[case("var c = 0\n\n\n\nvar b = 0", "var c = 0\n\nvar b = 0\n")]
+    #[case("const A=0\nconst  B :int=1", "const A = 0\nconst B: int = 1\n")]
     fn enforce_spacing_rules(#[case] source_input: &str, #[case] expected_output: &str) {
         let formatted = format_code(source_input).unwrap();
 
         assert_eq!(formatted, expected_output);
     }
+
+    #[rstest]
+    #[case("var a = # c\n\t1", "var a =  # c\n\t1\n")]
+    fn break_line_after_comments(#[case] source_input: &str, #[case] expected_output: &str) {
+        let formatted = format_code(source_input).unwrap();
+
+        assert_eq!(formatted, expected_output);
+    }
 }
@@ -1,3 +1,5 @@
+pub const TAB_WIDTH: usize = 4;
+
 pub fn force_end_line(buffer: &mut String) {
     while buffer.ends_with("\n") {
         buffer.pop();
@@ -11,6 +13,12 @@ pub fn indent_by(buffer: &mut String, indent_level: usize) {
     }
 }
 
+pub fn line_width(line: &str) -> usize {
+    line.chars()
+        .map(|c| if c == '\t' { TAB_WIDTH } else { 1 })
+        .sum()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -41,4 +49,14 @@ mod tests {
 
         assert_eq!(buf, output);
     }
+
+    #[rstest]
+    #[case("", 0)]
+    #[case("abc", 3)]
+    #[case("\tabc", 7)]
+    #[case("\t\ta", 9)]
+    #[case("ção", 3)]
+    fn count_tabs_as_tab_width(#[case] input: &str, #[case] output: usize) {
+        assert_eq!(line_width(input), output);
+    }
 }